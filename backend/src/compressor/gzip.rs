@@ -1,4 +1,4 @@
-use crate::compressor::rle::CompressedGrid;
+use crate::compressor::rle::{CompressedAnimation, CompressedGrid};
 use flate2::read::GzEncoder;
 use flate2::Compression;
 use std::io::Read;
@@ -112,6 +112,37 @@ pub fn deserialize_compressed_grid(data: &[u8]) -> Result<CompressedGrid, GzipEr
         .map_err(|e| GzipError::DeserializationError(e.to_string()))
 }
 
+/// Serializes a CompressedAnimation to JSON bytes.
+///
+/// The animation counterpart of `serialize_compressed_grid`.
+///
+/// # Arguments
+/// * `animation` - RLE-compressed animation frames.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - JSON-serialized bytes.
+/// * `Err(GzipError)` - Serialization failure.
+pub fn serialize_compressed_animation(animation: &CompressedAnimation) -> Result<Vec<u8>, GzipError> {
+    serde_json::to_vec(animation)
+        .map_err(|e| GzipError::SerializationError(e.to_string()))
+}
+
+/// Deserializes JSON bytes back to CompressedAnimation.
+///
+/// This function is only available during testing for validation purposes.
+///
+/// # Arguments
+/// * `data` - JSON-serialized bytes.
+///
+/// # Returns
+/// * `Ok(CompressedAnimation)` - Reconstructed animation data.
+/// * `Err(GzipError)` - Deserialization failure.
+#[cfg(test)]
+pub fn deserialize_compressed_animation(data: &[u8]) -> Result<CompressedAnimation, GzipError> {
+    serde_json::from_slice(data)
+        .map_err(|e| GzipError::DeserializationError(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,5 +15,19 @@ pub fn compress_ascii_grid(grid: &[Vec<crate::converter::ascii_pixel::AsciiPixel
     // Stage 3: Gzip compression
     let final_compressed = gzip::compress(&serialized)?;
     
+    Ok(final_compressed)
+}
+
+// Animation counterpart - every frame is RLE compressed, then the whole sequence is gzipped
+pub fn compress_ascii_animation(animation: &crate::converter::AsciiAnimation) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    // Stage 1: RLE compression of each frame
+    let rle_compressed = rle::compress_animation(animation)?;
+
+    // Stage 2: Serialize RLE data
+    let serialized = gzip::serialize_compressed_animation(&rle_compressed)?;
+
+    // Stage 3: Gzip compression
+    let final_compressed = gzip::compress(&serialized)?;
+
     Ok(final_compressed)
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
-use crate::converter::AsciiPixel;
-use crate::compressor::rle::types::{RleEntry, CompressedGrid, CompressionError};
+use crate::converter::{AsciiAnimation, AsciiPixel};
+use crate::compressor::rle::types::{RleEntry, CompressedAnimation, CompressedFrame, CompressedGrid, CompressionError};
 
 /// Compress an ASCII grid using run-length encoding
 pub fn compress_grid(grid: &[Vec<AsciiPixel>]) -> Result<CompressedGrid, CompressionError> {
@@ -10,14 +10,29 @@ pub fn compress_grid(grid: &[Vec<AsciiPixel>]) -> Result<CompressedGrid, Compres
     
     // Check if any pixel has color
     let has_color = grid.iter().any(|row| row.iter().any(|pixel| pixel.rgb.is_some()));
+    let has_background = grid.iter().any(|row| row.iter().any(|pixel| pixel.bg.is_some()));
+    let has_alpha = grid.iter().any(|row| row.iter().any(|pixel| pixel.alpha.is_some()));
 
     let mut compressed = CompressedGrid::new(width, height, has_color);
+    compressed.has_background = has_background;
+    compressed.has_alpha = has_alpha;
 
     for row in grid { compressed.rows.push(compress_row(row)); }
 
     Ok(compressed)
 }
 
+/// Compress every frame of an animation using run-length encoding
+pub fn compress_animation(animation: &AsciiAnimation) -> Result<CompressedAnimation, CompressionError> {
+    let frames = animation
+        .frames
+        .iter()
+        .map(|frame| Ok(CompressedFrame { delay_ms: frame.delay_ms, grid: compress_grid(&frame.grid)? }))
+        .collect::<Result<Vec<_>, CompressionError>>()?;
+
+    Ok(CompressedAnimation { width: animation.width, height: animation.height, frames })
+}
+
 /// Compress a single row using run-length encoding
 fn compress_row(row: &[AsciiPixel]) -> Vec<RleEntry> {
     let mut compressed_row = Vec::new();
@@ -28,7 +43,7 @@ fn compress_row(row: &[AsciiPixel]) -> Vec<RleEntry> {
     let mut count = 1u32;
 
     for pixel in row.iter().skip(1) {
-        // Check if pixels are the same (character AND color must match)
+        // Check if pixels are the same (character AND colors must match)
         if pixels_equal(pixel, &current_pixel) {
             count += 1;
         } else {
@@ -52,9 +67,9 @@ fn compress_row(row: &[AsciiPixel]) -> Vec<RleEntry> {
     compressed_row
 }
 
-/// Check if two pixels are equal (character and color must match)
+/// Check if two pixels are equal (character, both colors and opacity must match)
 fn pixels_equal(a: &AsciiPixel, b: &AsciiPixel) -> bool {
-    a.ch == b.ch && a.rgb == b.rgb
+    a.ch == b.ch && a.rgb == b.rgb && a.bg == b.bg && a.alpha == b.alpha
 }
 
 #[cfg(test)]
@@ -73,7 +88,7 @@ mod tests {
 
     #[test]
     fn test_single_pixel() {
-        let grid = vec![vec![AsciiPixel{ch: 'A', rgb: None}]];
+        let grid = vec![vec![AsciiPixel{ch: 'A'.into(), rgb: None, bg: None, alpha: None}]];
         let compressed = compress_grid(&grid).unwrap();
         assert_eq!(compressed.width, 1);
         assert_eq!(compressed.height, 1);
@@ -1,5 +1,5 @@
-use crate::converter::AsciiPixel;
-use crate::compressor::rle::types::{CompressedGrid, CompressionError};
+use crate::converter::{animation::AsciiFrame, AsciiAnimation, AsciiPixel};
+use crate::compressor::rle::types::{CompressedAnimation, CompressedGrid, CompressionError};
 
 /// Decompress a compressed grid back to the original ASCII grid
 pub fn decompress_grid(compressed: &CompressedGrid) -> Result<Vec<Vec<AsciiPixel>>, CompressionError> {
@@ -32,6 +32,17 @@ pub fn decompress_grid(compressed: &CompressedGrid) -> Result<Vec<Vec<AsciiPixel
     Ok(result)
 }
 
+/// Decompress every frame of a compressed animation
+pub fn decompress_animation(compressed: &CompressedAnimation) -> Result<AsciiAnimation, CompressionError> {
+    let frames = compressed
+        .frames
+        .iter()
+        .map(|frame| Ok(AsciiFrame { delay_ms: frame.delay_ms, grid: decompress_grid(&frame.grid)? }))
+        .collect::<Result<Vec<_>, CompressionError>>()?;
+
+    Ok(AsciiAnimation { width: compressed.width, height: compressed.height, frames })
+}
+
 /// Decompress a single row
 fn decompress_row(compressed_row: &[super::types::RleEntry]) -> Result<Vec<AsciiPixel>, CompressionError> {
     let mut row = Vec::new();
@@ -69,14 +80,14 @@ mod tests {
     fn test_roundtrip_compression() {
         let original = vec![
             vec![
-                AsciiPixel{ch: 'A', rgb: None},
-                AsciiPixel{ch: 'A', rgb: None},
-                AsciiPixel{ch: 'B', rgb: None},
+                AsciiPixel{ch: 'A'.into(), rgb: None, bg: None, alpha: None},
+                AsciiPixel{ch: 'A'.into(), rgb: None, bg: None, alpha: None},
+                AsciiPixel{ch: 'B'.into(), rgb: None, bg: None, alpha: None},
             ],
             vec![
-                AsciiPixel{ch: 'C', rgb: None},
-                AsciiPixel{ch: 'C', rgb: None},
-                AsciiPixel{ch: 'C', rgb: None},
+                AsciiPixel{ch: 'C'.into(), rgb: None, bg: None, alpha: None},
+                AsciiPixel{ch: 'C'.into(), rgb: None, bg: None, alpha: None},
+                AsciiPixel{ch: 'C'.into(), rgb: None, bg: None, alpha: None},
             ],
         ];
         
@@ -91,7 +102,7 @@ mod tests {
         let mut compressed = CompressedGrid::new(1, 1, false);
         compressed.rows.push(vec![RleEntry {
             count: 0,
-            pixel: AsciiPixel{ch: 'A', rgb: None},
+            pixel: AsciiPixel{ch: 'A'.into(), rgb: None, bg: None, alpha: None},
         }]);
         
         assert!(decompress_grid(&compressed).is_err());
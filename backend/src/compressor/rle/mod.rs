@@ -4,8 +4,8 @@ mod compress;
 #[cfg(test)]
 mod decompress;
 
-pub use types::CompressedGrid;
-pub use compress::compress_grid;
+pub use types::{CompressedAnimation, CompressedGrid};
+pub use compress::{compress_animation, compress_grid};
 
 #[cfg(test)]
-pub use decompress::decompress_grid;
\ No newline at end of file
+pub use decompress::{decompress_animation, decompress_grid};
\ No newline at end of file
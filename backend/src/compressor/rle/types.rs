@@ -15,6 +15,12 @@ pub struct CompressedGrid {
     pub width: u32,
     pub height: u32,
     pub has_color: bool,
+    /// Whether any pixel carries a background color.
+    #[serde(default)]
+    pub has_background: bool,
+    /// Whether any pixel carries an opacity value.
+    #[serde(default)]
+    pub has_alpha: bool,
     pub rows: Vec<Vec<RleEntry>>,
 }
 
@@ -25,11 +31,28 @@ impl CompressedGrid {
             width,
             height,
             has_color,
+            has_background: false,
+            has_alpha: false,
             rows: Vec::new(),
         }
     }
 }
 
+/// One RLE-compressed animation frame and its display time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressedFrame {
+    pub delay_ms: u32,
+    pub grid: CompressedGrid,
+}
+
+/// A compressed animation: frames in display order, all with the same dimensions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressedAnimation {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<CompressedFrame>,
+}
+
 /// Errors that can occur during compression/decompression
 #[derive(Debug)]
 #[allow(dead_code)]
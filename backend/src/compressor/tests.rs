@@ -15,8 +15,10 @@ mod tests {
             for col in 0..width {
                 let char_index = (row * width + col) % chars.len();
                 current_row.push(AsciiPixel {
-                    ch: chars[char_index],
+                    ch: chars[char_index].into(),
                     rgb: None,
+                    bg: None,
+                    alpha: None,
                 });
             }
             grid.push(current_row);
@@ -41,8 +43,10 @@ mod tests {
                 let char_index = (row * width + col) % chars.len();
                 let color_index = (row * width + col) % colors.len();
                 current_row.push(AsciiPixel {
-                    ch: chars[char_index],
+                    ch: chars[char_index].into(),
                     rgb: Some(colors[color_index]),
+                    bg: None,
+                    alpha: None,
                 });
             }
             grid.push(current_row);
@@ -56,7 +60,7 @@ mod tests {
         let mut row = Vec::new();
         
         for (ch, rgb) in pixels {
-            row.push(AsciiPixel { ch, rgb });
+            row.push(AsciiPixel { ch: ch.into(), rgb, bg: None, alpha: None });
         }
         grid.push(row);
         grid
@@ -75,6 +79,7 @@ mod tests {
             assert_eq!(compressed.width, 10);
             assert_eq!(compressed.height, 1);
             assert_eq!(compressed.has_color, false);
+            assert!(!compressed.has_background);
             assert_eq!(compressed.rows.len(), 1);
 
             let first_row = &compressed.rows[0];
@@ -217,6 +222,59 @@ mod tests {
             assert_eq!(compressed.rows[0][1].pixel.rgb, None);
         }
 
+        #[test]
+        fn test_rle_format_background_colors() {
+            let red = [255, 0, 0];
+            let blue = [0, 0, 255];
+
+            // Row: same glyph and foreground, but the background changes halfway
+            let mut grid = create_specific_colored_grid(vec![
+                ('▀', Some(red)),
+                ('▀', Some(red)),
+                ('▀', Some(red)),
+            ]);
+            grid[0][0].bg = Some(blue);
+            grid[0][1].bg = Some(blue);
+
+            let compressed = compress_grid(&grid).expect("Compression should succeed");
+
+            assert!(compressed.has_background);
+            assert_eq!(compressed.rows[0].len(), 2, "Should not compress across background boundary");
+            assert_eq!(compressed.rows[0][0].count, 2);
+            assert_eq!(compressed.rows[0][0].pixel.bg, Some(blue));
+            assert_eq!(compressed.rows[0][1].count, 1);
+            assert_eq!(compressed.rows[0][1].pixel.bg, None);
+
+            let decompressed = decompress_grid(&compressed).expect("Decompression should succeed");
+            assert_eq!(decompressed, grid);
+        }
+
+        #[test]
+        fn test_rle_format_alpha() {
+            let white = [255, 255, 255];
+
+            // Row: same glyph and color, opaque then half transparent
+            let mut grid = create_specific_colored_grid(vec![
+                ('#', Some(white)),
+                ('#', Some(white)),
+                ('#', Some(white)),
+            ]);
+            grid[0][0].alpha = Some(255);
+            grid[0][1].alpha = Some(255);
+            grid[0][2].alpha = Some(128);
+
+            let compressed = compress_grid(&grid).expect("Compression should succeed");
+
+            assert!(compressed.has_alpha);
+            assert!(!compressed.has_background);
+            assert_eq!(compressed.rows[0].len(), 2, "Should not compress across opacity boundary");
+            assert_eq!(compressed.rows[0][0].pixel.alpha, Some(255));
+            assert_eq!(compressed.rows[0][1].pixel.alpha, Some(128));
+
+            let decompressed = decompress_grid(&compressed).expect("Decompression should succeed");
+            assert_eq!(decompressed, grid);
+        }
+
         #[test]
         fn test_rle_format_empty_grid() {
             let grid: Vec<Vec<AsciiPixel>> = vec![];
@@ -381,6 +439,30 @@ mod tests {
             assert_eq!(rle_data.has_color, true, "Color information should be preserved");
         }
 
+        #[test]
+        fn test_combined_grapheme_clusters() {
+            use crate::converter::glyph::Glyph;
+
+            // A combining sequence, a ZWJ emoji and a CJK glyph, with continuation cells after the wide ones
+            let glyphs = ["e\u{301}", "👨\u{200D}👩\u{200D}👧", "", "漢", "", "漢", ""];
+            let row: Vec<AsciiPixel> = glyphs
+                .iter()
+                .map(|glyph| AsciiPixel { ch: Glyph::new(glyph), rgb: Some([1, 2, 3]), bg: None, alpha: None })
+                .collect();
+            let grid = vec![row];
+
+            let rle_compressed = compress_grid(&grid).expect("RLE compression should succeed");
+            assert_eq!(rle_compressed.rows[0].len(), 7, "Continuations must stay separate runs from their wide glyphs");
+            let serialized = gzip::serialize_compressed_grid(&rle_compressed).expect("Serialization should succeed");
+            let final_compressed = gzip::compress(&serialized).expect("Gzip compression should succeed");
+
+            let decompressed_gzip = gzip::decompress(&final_compressed).expect("Gzip decompression should succeed");
+            let rle_data = gzip::deserialize_compressed_grid(&decompressed_gzip).expect("Deserialization should succeed");
+            let final_grid = decompress_grid(&rle_data).expect("RLE decompression should succeed");
+
+            assert_eq!(grid, final_grid, "Multi-code-point glyphs and continuations should survive the round trip");
+        }
+
         #[test]
         fn test_combined_alternating_pattern() {
             // Test with a pattern that doesn't compress well with RLE
@@ -482,4 +564,80 @@ mod tests {
             assert_eq!(grid, decompressed, "End-to-end pipeline should preserve data");
         }
     }
+
+    // Animation tests
+    mod animation_tests {
+        use super::*;
+        use crate::converter::{animation::AsciiFrame, AsciiAnimation};
+        use crate::compressor::rle::{compress_animation, decompress_animation};
+
+        fn create_test_animation() -> AsciiAnimation {
+            AsciiAnimation {
+                width: 12,
+                height: 3,
+                frames: vec![
+                    AsciiFrame { delay_ms: 100, grid: create_test_grid(12, 3, "AAAABBBB") },
+                    AsciiFrame { delay_ms: 40, grid: create_colored_test_grid(12, 3, "@@  ") },
+                ],
+            }
+        }
+
+        #[test]
+        fn test_animation_roundtrip() {
+            let animation = create_test_animation();
+            let compressed = compress_animation(&animation).expect("Compression should succeed");
+
+            assert_eq!(compressed.frames.len(), 2);
+            assert_eq!(compressed.frames[0].delay_ms, 100);
+            assert_eq!(compressed.frames[1].delay_ms, 40);
+            assert!(!compressed.frames[0].grid.has_color);
+            assert!(compressed.frames[1].grid.has_color);
+
+            let decompressed = decompress_animation(&compressed).expect("Decompression should succeed");
+            assert_eq!(decompressed, animation);
+        }
+
+        #[test]
+        fn test_animation_full_pipeline() {
+            let animation = create_test_animation();
+            let bytes = crate::compressor::compress_ascii_animation(&animation).expect("Compression should succeed");
+
+            let json = gzip::decompress(&bytes).expect("Gzip decompression should succeed");
+            let compressed = gzip::deserialize_compressed_animation(&json).expect("Deserialization should succeed");
+            let decompressed = decompress_animation(&compressed).expect("Decompression should succeed");
+            assert_eq!(decompressed, animation);
+        }
+
+        #[test]
+        fn test_converted_gif_full_pipeline() {
+            use crate::converter::core::Converter;
+            use image::{codecs::gif::GifEncoder, Delay, Frame, Rgba, RgbaImage};
+
+            // Three frames of different colors and display times, drawn at different spots
+            let mut gif = Vec::new();
+            {
+                let mut encoder = GifEncoder::new(&mut gif);
+                for (i, (color, delay_ms)) in [([255, 0, 0, 255], 100), ([0, 0, 255, 255], 40), ([0, 255, 0, 255], 250)].into_iter().enumerate() {
+                    let buffer = RgbaImage::from_fn(16, 8, |x, _| if x / 6 == i as u32 { Rgba(color) } else { Rgba([0, 0, 0, 255]) });
+                    encoder.encode_frame(Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))).unwrap();
+                }
+            }
+
+            let config = serde_json::from_value(serde_json::json!({ "output_width": 12, "color_mode": "truecolor" })).unwrap();
+            let animation = Converter::convert_animation_from_bytes(&gif, config).expect("Conversion should succeed");
+            assert_eq!(animation.frames.iter().map(|frame| frame.delay_ms).collect::<Vec<_>>(), [100, 40, 250]);
+            assert_eq!(animation.width, 12);
+            for frame in &animation.frames {
+                assert_eq!(frame.grid.len(), animation.height as usize);
+                assert!(frame.grid.iter().all(|row| row.len() == animation.width as usize));
+            }
+            assert_ne!(animation.frames[0].grid, animation.frames[1].grid);
+
+            let bytes = crate::compressor::compress_ascii_animation(&animation).expect("Compression should succeed");
+            let json = gzip::decompress(&bytes).expect("Gzip decompression should succeed");
+            let compressed = gzip::deserialize_compressed_animation(&json).expect("Deserialization should succeed");
+            let decompressed = decompress_animation(&compressed).expect("Decompression should succeed");
+            assert_eq!(decompressed, animation);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use std::io::Cursor;
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
+    AnimationDecoder, DynamicImage, Frame, ImageFormat,
+};
+use serde::{Serialize, Deserialize};
+use crate::converter::{ascii_pixel::AsciiPixel, error::ConverterError};
+
+/// One converted frame and how long it stays on screen.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AsciiFrame {
+    /// Display time in milliseconds (0 for still images).
+    pub delay_ms: u32,
+    pub grid: Vec<Vec<AsciiPixel>>,
+}
+
+/// A converted animation. Every frame has the same `width` x `height` grid.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AsciiAnimation {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<AsciiFrame>,
+}
+
+/// A decoded frame: the full canvas after the previous frame's disposal and this
+/// frame's blending have been applied, plus its delay.
+pub struct DecodedFrame {
+    pub image: DynamicImage,
+    pub delay_ms: u32,
+}
+
+/// Frames decoded one at a time, in display order.
+pub type DecodedFrames<'a> = Box<dyn Iterator<Item = Result<DecodedFrame, ConverterError>> + 'a>;
+
+/// Decodes up to `max_frames` frames of an animated GIF, APNG or animated WebP.
+/// Frames are decoded as the iterator advances, so only the current canvas is held in
+/// memory. Still images (of any format) decode to a single frame with no delay.
+pub fn decode_frames(image_bytes: &[u8], max_frames: usize) -> Result<DecodedFrames<'_>, ConverterError> {
+    let frames = match image::guess_format(image_bytes)? {
+        ImageFormat::Gif => Some(GifDecoder::new(Cursor::new(image_bytes))?.into_frames()),
+        ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(image_bytes))?;
+            decoder.is_apng().then(|| decoder.apng().into_frames())
+        }
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(image_bytes))?;
+            decoder.has_animation().then(|| decoder.into_frames())
+        }
+        _ => None,
+    };
+
+    match frames {
+        Some(frames) => Ok(Box::new(frames.take(max_frames).map(|frame| decoded(frame?)))),
+        None => {
+            let image = image::load_from_memory(image_bytes)?;
+            Ok(Box::new(std::iter::once(Ok(DecodedFrame { image, delay_ms: 0 }))))
+        }
+    }
+}
+
+/// Wraps a composited frame, converting its delay to whole milliseconds.
+fn decoded(frame: Frame) -> Result<DecodedFrame, ConverterError> {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    Ok(DecodedFrame {
+        delay_ms: (numer as f64 / denom.max(1) as f64).round() as u32,
+        image: DynamicImage::ImageRgba8(frame.into_buffer()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{codecs::gif::GifEncoder, Delay, Rgba, RgbaImage};
+
+    fn decode(bytes: &[u8], max_frames: usize) -> Vec<DecodedFrame> {
+        decode_frames(bytes, max_frames).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    fn encode_gif(colors: &[[u8; 4]], delay_ms: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut bytes);
+            for &color in colors {
+                let buffer = RgbaImage::from_pixel(4, 4, Rgba(color));
+                let frame = Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1));
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_gif_frames_and_delays() {
+        let bytes = encode_gif(&[[255, 0, 0, 255], [0, 0, 255, 255], [0, 255, 0, 255]], 120);
+        let frames = decode(&bytes, 100);
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| frame.delay_ms == 120));
+        assert_eq!(frames[1].image.to_rgba8().get_pixel(0, 0)[2], 255);
+    }
+
+    #[test]
+    fn test_frame_limit() {
+        let bytes = encode_gif(&[[0, 0, 0, 255]; 5], 50);
+        assert_eq!(decode(&bytes, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_still_image_is_one_frame() {
+        let mut bytes = Vec::new();
+        RgbaImage::new(3, 3)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        let frames = decode(&bytes, 10);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].delay_ms, 0);
+    }
+}
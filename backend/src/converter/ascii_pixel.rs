@@ -1,8 +1,16 @@
 use serde::{Serialize, Deserialize};
+use crate::converter::glyph::Glyph;
 
-/// Represents a single ASCII pixel, with optional color.
+/// Represents a single ASCII pixel, with optional foreground and background color and opacity.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AsciiPixel {
-    pub ch: char,
+    /// The glyph drawn in this cell; an empty string continues the wide glyph to its left.
+    pub ch: Glyph,
     pub rgb: Option<[u8; 3]>, // None for no-color output
+    /// Background color behind the glyph, used by block mosaic modes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<[u8; 3]>,
+    /// Cell opacity (0 = fully transparent), present when the source alpha is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<u8>,
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+use crate::converter::{ascii_pixel::AsciiPixel, config::DitherMode, dither};
+
+/// Dots per cell horizontally.
+pub const DOT_COLUMNS: u32 = 2;
+/// Dots per cell vertically.
+pub const DOT_ROWS: u32 = 4;
+
+/// First code point of the Unicode Braille Patterns block (blank pattern).
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Bit for each dot position, indexed as `[row][column]`.
+/// Braille numbers dots 1-3 and 4-6 down the two columns, with dots 7 and 8 added below.
+const DOT_BITS: [[u8; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+/// Renders Braille cells from a dot-resolution luminance grid (row-major,
+/// `width * 2` by `height * 4`). Dots brighter than `threshold` are raised;
+/// with dithering the threshold shifts the grid before error diffusion.
+/// When `colors` is given (same layout as `luma`), each cell gets its average color.
+pub fn render(
+    luma: &[f32],
+    colors: Option<&[[u8; 3]]>,
+    width: u32,
+    height: u32,
+    threshold: u8,
+    dither_mode: DitherMode,
+) -> Vec<Vec<AsciiPixel>> {
+    let dot_width = (width * DOT_COLUMNS) as usize;
+    let dot_height = (height * DOT_ROWS) as usize;
+
+    let raised: Vec<bool> = match dither_mode {
+        DitherMode::None => luma.iter().map(|&v| v >= threshold as f32).collect(),
+        _ => {
+            // Move the requested threshold onto the midpoint the ditherer rounds at
+            let offset = 128.0 - threshold as f32;
+            let shifted: Vec<f32> = luma.iter().map(|&v| v + offset).collect();
+            dither::quantize(&shifted, dot_width, dot_height, 2, dither_mode)
+                .into_iter()
+                .map(|level| level == 1)
+                .collect()
+        }
+    };
+
+    (0..height as usize)
+        .map(|y| {
+            (0..width as usize)
+                .map(|x| {
+                    let mut mask = 0u32;
+                    let mut sum = [0u32; 3];
+                    for (row, bits) in DOT_BITS.iter().enumerate() {
+                        for (column, bit) in bits.iter().enumerate() {
+                            let index = (y * DOT_ROWS as usize + row) * dot_width + x * DOT_COLUMNS as usize + column;
+                            if raised[index] {
+                                mask |= *bit as u32;
+                            }
+                            if let Some(colors) = colors {
+                                for (total, value) in sum.iter_mut().zip(colors[index]) {
+                                    *total += value as u32;
+                                }
+                            }
+                        }
+                    }
+
+                    let dots = DOT_COLUMNS * DOT_ROWS;
+                    AsciiPixel {
+                        ch: char::from_u32(BRAILLE_BASE + mask).unwrap_or(' ').into(),
+                        rgb: colors.map(|_| sum.map(|total| (total / dots) as u8)),
+                        bg: None,
+                        alpha: None,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_and_full_cells() {
+        let grid = render(&[0.0; 8], None, 1, 1, 128, DitherMode::None);
+        assert_eq!(grid[0][0].ch, '\u{2800}');
+        let grid = render(&[255.0; 8], None, 1, 1, 128, DitherMode::None);
+        assert_eq!(grid[0][0].ch, '\u{28FF}');
+    }
+
+    #[test]
+    fn test_dot_numbering() {
+        // Only the top-right dot (dot 4) and the bottom-left dot (dot 7) are lit
+        let mut luma = [0.0; 8];
+        luma[1] = 255.0;
+        luma[6] = 255.0;
+        let grid = render(&luma, None, 1, 1, 128, DitherMode::None);
+        assert_eq!(grid[0][0].ch, char::from_u32(0x2800 + 0x08 + 0x40).unwrap());
+    }
+
+    #[test]
+    fn test_average_color() {
+        let colors = [[200, 0, 0], [0, 0, 200], [200, 0, 0], [0, 0, 200], [200, 0, 0], [0, 0, 200], [200, 0, 0], [0, 0, 200]];
+        let grid = render(&[0.0; 8], Some(&colors), 1, 1, 128, DitherMode::None);
+        assert_eq!(grid[0][0].rgb, Some([100, 0, 100]));
+    }
+
+    #[test]
+    fn test_threshold_with_dithering() {
+        // A mid-grey below the threshold should stay blank even when dithered
+        let grid = render(&[60.0; 32], None, 2, 2, 250, DitherMode::FloydSteinberg);
+        assert!(grid.iter().flatten().all(|p| p.ch == '\u{2800}'));
+    }
+}
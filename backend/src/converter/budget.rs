@@ -0,0 +1,107 @@
+use crate::converter::{ascii_pixel::AsciiPixel, config::ColorMode};
+
+/// Length of the reset sequence `ESC[0m`.
+const RESET_LEN: usize = 4;
+
+/// Length of `ESC[39m` or `ESC[49m`, which return the foreground or background to the default.
+const DEFAULT_COLOR_LEN: usize = 5;
+
+/// Length of the SGR sequence that sets one color. `background` selects the `4x`/`10x`
+/// codes instead of `3x`/`9x`. Palette modes are counted at their longest form, so the
+/// estimate never undercounts: `ESC[38;5;255m` for xterm256 and `ESC[107m` for ANSI 16.
+fn sgr_len(rgb: [u8; 3], background: bool, mode: ColorMode) -> usize {
+    match mode {
+        ColorMode::None => 0,
+        ColorMode::Xterm256 => 11,
+        ColorMode::Ansi16 => if background { 6 } else { 5 },
+        // ESC[38;2;R;G;Bm
+        ColorMode::Truecolor | ColorMode::GrayscaleTint | ColorMode::Palette | ColorMode::GradientMap => {
+            10 + rgb.iter().map(|&v| v.to_string().len()).sum::<usize>()
+        }
+    }
+}
+
+/// Number of characters (code points) in the grid as text: every glyph, plus a newline
+/// between rows. With `ansi`, color escape sequences are counted too: each run of cells
+/// with the same colors starts with the codes for its foreground and background, a run
+/// that drops one of the two colors starts with the code that returns it to the default,
+/// an uncolored run after a colored one starts with a reset, and every row that used
+/// color ends with a reset.
+pub fn text_length(grid: &[Vec<AsciiPixel>], mode: ColorMode, ansi: bool) -> usize {
+    let glyphs: usize = grid.iter().flatten().map(|pixel| pixel.ch.as_str().chars().count()).sum();
+    let newlines = grid.len().saturating_sub(1);
+    if !ansi {
+        return glyphs + newlines;
+    }
+
+    let mut escapes = 0;
+    for row in grid {
+        let mut current = (None, None);
+        let mut colored = false;
+        for pixel in row {
+            let colors = (pixel.rgb, pixel.bg);
+            if colors == current {
+                continue;
+            }
+            escapes += match colors {
+                (None, None) => RESET_LEN,
+                (fg, bg) => {
+                    colored = true;
+                    let dropped = [(current.0, fg), (current.1, bg)]
+                        .iter()
+                        .filter(|(before, after)| before.is_some() && after.is_none())
+                        .count();
+                    dropped * DEFAULT_COLOR_LEN
+                        + fg.map_or(0, |rgb| sgr_len(rgb, false, mode))
+                        + bg.map_or(0, |rgb| sgr_len(rgb, true, mode))
+                }
+            };
+            current = colors;
+        }
+        if colored && current != (None, None) {
+            escapes += RESET_LEN;
+        }
+    }
+    glyphs + newlines + escapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::glyph::Glyph;
+
+    fn cell(ch: &str, rgb: Option<[u8; 3]>) -> AsciiPixel {
+        AsciiPixel { ch: Glyph::new(ch), rgb, bg: None, alpha: None }
+    }
+
+    #[test]
+    fn test_plain_text_length() {
+        let grid = vec![
+            vec![cell("#", None), cell("🔥", None), cell("", None)],
+            vec![cell(" ", None), cell("e\u{301}", None), cell(".", None)],
+        ];
+        // 1 + 1 + 0 glyph code points, then 1 + 2 + 1, and one newline
+        assert_eq!(text_length(&grid, ColorMode::None, false), 7);
+        assert_eq!(text_length(&grid, ColorMode::None, true), 7);
+    }
+
+    #[test]
+    fn test_ansi_runs() {
+        let red = Some([255, 0, 0]);
+        let grid = vec![vec![cell("#", red), cell("#", red), cell(" ", None), cell("#", Some([7, 7, 7]))]];
+        // ESC[38;2;255;0;0m (15), reset (4), ESC[38;2;7;7;7m (13), final reset (4)
+        assert_eq!(text_length(&grid, ColorMode::Truecolor, true), 4 + 15 + 4 + 13 + 4);
+        assert_eq!(text_length(&grid, ColorMode::Truecolor, false), 4);
+        assert_eq!(text_length(&grid, ColorMode::Ansi16, true), 4 + 5 + 4 + 5 + 4);
+    }
+
+    #[test]
+    fn test_dropped_background_is_reset() {
+        let red = Some([255, 0, 0]);
+        let on_black = AsciiPixel { bg: Some([0, 0, 0]), ..cell("#", red) };
+        let grid = vec![vec![on_black, cell("#", red)]];
+        // ESC[38;2;255;0;0m (15) and ESC[48;2;0;0;0m (13), then ESC[49m (5) and the
+        // foreground again (15), and the final reset (4)
+        assert_eq!(text_length(&grid, ColorMode::Truecolor, true), 2 + 15 + 13 + 5 + 15 + 4);
+    }
+}
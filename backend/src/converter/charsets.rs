@@ -0,0 +1,84 @@
+use serde::Serialize;
+use crate::converter::glyph::Glyph;
+
+/// A named character set, ordered from darkest (least ink) to brightest (most ink).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CharsetPreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub characters: &'static str,
+}
+
+impl CharsetPreset {
+    /// The preset's characters as a `character_set`.
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        Glyph::split(self.characters)
+    }
+}
+
+/// Built-in character set presets, selectable by name in `character_set`.
+pub const PRESETS: &[CharsetPreset] = &[
+    CharsetPreset {
+        name: "standard",
+        description: "10-level ASCII ramp (the default)",
+        characters: " .:-=+*#%@",
+    },
+    CharsetPreset {
+        name: "simple",
+        description: "6-level ASCII ramp for small outputs",
+        characters: " .-*#@",
+    },
+    CharsetPreset {
+        name: "complex",
+        description: "70-level ASCII ramp for fine tonal detail",
+        characters: " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$",
+    },
+    CharsetPreset {
+        name: "blocks",
+        description: "Unicode shade blocks",
+        characters: " ░▒▓█",
+    },
+    CharsetPreset {
+        name: "dots",
+        description: "Dots of increasing size",
+        characters: " .·•●",
+    },
+    CharsetPreset {
+        name: "digits",
+        description: "Digits ordered by ink coverage",
+        characters: " 7014236985",
+    },
+    CharsetPreset {
+        name: "katakana",
+        description: "Half-width katakana, for a digital rain look",
+        characters: " ｰｨｼﾉﾘｿﾂﾝﾃﾄﾕﾖﾗﾒﾈﾑﾎﾏﾜ",
+    },
+];
+
+/// Looks up a preset by name (case-insensitive).
+pub fn lookup(name: &str) -> Option<&'static CharsetPreset> {
+    PRESETS.iter().find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("complex").unwrap().glyphs().len(), 70);
+        assert_eq!(lookup("Blocks").unwrap().characters, " ░▒▓█");
+        assert!(lookup("missing").is_none());
+    }
+
+    #[test]
+    fn test_presets_are_well_formed() {
+        for preset in PRESETS {
+            let glyphs = preset.glyphs();
+            assert!(glyphs.len() >= 2, "{} is too short", preset.name);
+            assert_eq!(glyphs[0], ' ', "{} should start with a blank", preset.name);
+            let unique: std::collections::HashSet<&Glyph> = glyphs.iter().collect();
+            assert_eq!(unique.len(), glyphs.len(), "{} has duplicate characters", preset.name);
+        }
+    }
+}
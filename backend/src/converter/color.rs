@@ -0,0 +1,154 @@
+use std::sync::OnceLock;
+use crate::converter::config::{IntensitySource, LuminanceModel};
+
+/// Lookup table from 8-bit sRGB to linear light (0.0-1.0).
+fn decode_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0)))
+}
+
+/// Decodes an sRGB-encoded value (0.0-1.0) to linear light.
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear-light value (0.0-1.0) with the sRGB transfer curve.
+pub fn linear_to_srgb(v: f32) -> f32 {
+    let v = v.clamp(0.0, 1.0);
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decodes an 8-bit sRGB channel to linear light using a lookup table.
+pub fn decode(v: u8) -> f32 {
+    decode_table()[v as usize]
+}
+
+/// Encodes a linear-light channel back to 8-bit sRGB.
+pub fn encode(v: f32) -> u8 {
+    (linear_to_srgb(v) * 255.0).round() as u8
+}
+
+/// Relative luminance (Rec. 709 / sRGB primaries) of an 8-bit sRGB color, in linear light.
+pub fn relative_luminance(rgb: [u8; 3]) -> f32 {
+    0.2126 * decode(rgb[0]) + 0.7152 * decode(rgb[1]) + 0.0722 * decode(rgb[2])
+}
+
+/// CIE 1976 lightness L* (0-100) for a linear relative luminance.
+pub fn lightness(y: f32) -> f32 {
+    const EPSILON: f32 = 216.0 / 24389.0;
+    const KAPPA: f32 = 24389.0 / 27.0;
+    if y > EPSILON {
+        116.0 * y.cbrt() - 16.0
+    } else {
+        KAPPA * y
+    }
+}
+
+/// Converts an 8-bit sRGB color to OKLab (L in 0.0-1.0), a perceptually uniform space
+/// where Euclidean distance tracks how different two colors look.
+pub fn oklab(rgb: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(decode);
+    let l = (0.4122215 * r + 0.5363325 * g + 0.051446 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.107397 * b).cbrt();
+    let s = (0.0883025 * r + 0.2817188 * g + 0.6299787 * b).cbrt();
+    [
+        0.2104543 * l + 0.7936178 * m - 0.0040720 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.0259040 * l + 0.7827718 * m - 0.8086758 * s,
+    ]
+}
+
+/// Computes a 0-255 intensity for an 8-bit sRGB color using the chosen model.
+pub fn luminance(rgb: [u8; 3], model: LuminanceModel) -> u8 {
+    let value = match model {
+        LuminanceModel::Rec601 => 0.299 * rgb[0] as f32 + 0.587 * rgb[1] as f32 + 0.114 * rgb[2] as f32,
+        LuminanceModel::Rec709 => linear_to_srgb(relative_luminance(rgb)) * 255.0,
+        LuminanceModel::CieLightness => lightness(relative_luminance(rgb)) * 2.55,
+    };
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+/// Computes the 0-255 intensity that drives glyph selection from the chosen channel.
+/// `model` is used by the luminance-based sources.
+pub fn intensity(rgb: [u8; 3], source: IntensitySource, model: LuminanceModel) -> u8 {
+    let max = rgb.into_iter().max().unwrap_or(0);
+    match source {
+        IntensitySource::Luminance => luminance(rgb, model),
+        IntensitySource::InvertedLuminance => 255 - luminance(rgb, model),
+        IntensitySource::Lightness => luminance(rgb, LuminanceModel::CieLightness),
+        IntensitySource::Value => max,
+        IntensitySource::Saturation => {
+            let min = rgb.into_iter().min().unwrap_or(0);
+            if max == 0 { 0 } else { ((max - min) as u32 * 255 / max as u32) as u8 }
+        }
+        IntensitySource::Red => rgb[0],
+        IntensitySource::Green => rgb[1],
+        IntensitySource::Blue => rgb[2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_roundtrip() {
+        for v in [0u8, 1, 10, 64, 128, 200, 255] {
+            assert_eq!(encode(decode(v)), v);
+        }
+    }
+
+    #[test]
+    fn test_models_agree_on_extremes() {
+        for model in [LuminanceModel::Rec601, LuminanceModel::Rec709, LuminanceModel::CieLightness] {
+            assert_eq!(luminance([0, 0, 0], model), 0);
+            assert_eq!(luminance([255, 255, 255], model), 255);
+        }
+    }
+
+    #[test]
+    fn test_neutral_grey_keeps_its_value() {
+        // A neutral grey is its own sRGB-encoded luminance
+        assert_eq!(luminance([128, 128, 128], LuminanceModel::Rec709), 128);
+        // L* of sRGB mid-grey is ~53.6
+        assert_eq!(luminance([128, 128, 128], LuminanceModel::CieLightness), 137);
+    }
+
+    #[test]
+    fn test_oklab_reference_values() {
+        let white = oklab([255, 255, 255]);
+        assert!((white[0] - 1.0).abs() < 1e-3 && white[1].abs() < 1e-3 && white[2].abs() < 1e-3);
+        let red = oklab([255, 0, 0]);
+        assert!((red[0] - 0.628).abs() < 1e-3 && (red[1] - 0.225).abs() < 1e-3 && (red[2] - 0.126).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rec709_weights_green_more() {
+        let green = luminance([0, 255, 0], LuminanceModel::Rec709);
+        let red = luminance([255, 0, 0], LuminanceModel::Rec709);
+        let blue = luminance([0, 0, 255], LuminanceModel::Rec709);
+        assert!(green > red && red > blue);
+    }
+
+    #[test]
+    fn test_intensity_sources() {
+        let orange = [255, 128, 0];
+        let source = |source| intensity(orange, source, LuminanceModel::Rec601);
+        assert_eq!(source(IntensitySource::Luminance), luminance(orange, LuminanceModel::Rec601));
+        assert_eq!(source(IntensitySource::InvertedLuminance), 255 - luminance(orange, LuminanceModel::Rec601));
+        assert_eq!(source(IntensitySource::Lightness), luminance(orange, LuminanceModel::CieLightness));
+        assert_eq!(source(IntensitySource::Value), 255);
+        assert_eq!(source(IntensitySource::Saturation), 255);
+        assert_eq!(source(IntensitySource::Green), 128);
+        assert_eq!(intensity([128, 128, 128], IntensitySource::Saturation, LuminanceModel::Rec601), 0);
+        assert_eq!(intensity([0, 0, 0], IntensitySource::Saturation, LuminanceModel::Rec601), 0);
+    }
+}
@@ -1,20 +1,513 @@
 use serde::{Serialize, Deserialize};
+use crate::converter::{charsets, glyph::Glyph};
 
 // ===== Default Value Functions =====
-fn default_charset()                -> Vec<char>    { vec![' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'] }
+fn default_charset()                -> Vec<Glyph>   { Glyph::split(" .:-=+*#%@") }
 fn default_output_width()           -> u32          { 200 }
 fn default_output_height()          -> Option<u32>  { None }
 fn default_brightness()             -> f32          { 1.0 }
 fn default_contrast()               -> f32          { 1.0 }
-fn default_is_color()               -> bool         { false }
+fn default_color_mode()             -> ColorMode    { ColorMode::None }
 fn default_aspect_ratio_correction()-> f32          { 0.55 }
+fn default_dither()                 -> DitherMode   { DitherMode::None }
+fn default_render_mode()            -> RenderMode   { RenderMode::Ramp }
+fn default_edge_detector()          -> EdgeDetector { EdgeDetector::Sobel }
+fn default_edge_threshold()         -> f32          { 48.0 }
+fn default_glyph_metric()           -> GlyphMetric  { GlyphMetric::Mse }
+fn default_braille_threshold()      -> u8           { 128 }
+fn default_cell_background()        -> CellBackground { CellBackground::None }
+fn default_resize_filter()          -> ResizeFilter { ResizeFilter::Lanczos3 }
+fn default_luminance_model()        -> LuminanceModel { LuminanceModel::Rec601 }
+fn default_intensity_source()       -> IntensitySource { IntensitySource::Luminance }
+fn default_background_theme()       -> BackgroundTheme { BackgroundTheme::Dark }
+fn default_linear_light()           -> bool         { false }
+fn default_auto_contrast()          -> AutoContrast { AutoContrast::None }
+fn default_auto_levels_percentile() -> f32          { 1.0 }
+fn default_clahe_clip_limit()       -> f32          { 2.0 }
+fn default_clahe_tiles()            -> u32          { 8 }
+fn default_alpha_mode()             -> AlphaMode    { AlphaMode::Ignore }
+fn default_alpha_background()       -> [u8; 3]      { [0, 0, 0] }
+fn default_alpha_threshold()        -> u8           { 128 }
+fn default_palette()                -> Vec<[u8; 3]> { Vec::new() }
+fn default_tint_color()             -> [u8; 3]      { [0, 255, 0] }
+fn default_color_dither()           -> DitherMode   { DitherMode::None }
+fn default_preprocess()             -> PreprocessConfig { PreprocessConfig::default() }
+fn default_max_frames()             -> u32          { 300 }
+fn default_density_calibration()    -> DensityCalibration { DensityCalibration::None }
+fn default_tone_curve()             -> ToneCurve    { ToneCurve::None }
+fn default_gamma()                  -> f32          { 1.0 }
+fn default_s_curve_strength()       -> f32          { 0.5 }
+fn default_curve_points()           -> Vec<[u8; 2]> { Vec::new() }
+fn default_char_thresholds()        -> Vec<u8>      { Vec::new() }
+fn default_emoji_table()            -> Vec<EmojiColor> { Vec::new() }
+fn default_emoji_builtin()          -> bool         { true }
+fn default_threshold_method()       -> ThresholdMethod { ThresholdMethod::Fixed }
+fn default_binary_threshold()       -> u8           { 128 }
+fn default_adaptive_window()        -> u32          { 15 }
+fn default_adaptive_offset()        -> f32          { 8.0 }
+fn default_fit()                    -> FitMode      { FitMode::Stretch }
+fn default_max_size()               -> Option<u32>  { None }
+fn default_max_chars()              -> Option<usize> { None }
+fn default_count_ansi()             -> bool         { false }
+fn default_no_upscale()             -> bool         { false }
+fn default_padding_char()           -> Glyph        { Glyph::from(' ') }
+fn default_gravity()                -> Gravity      { Gravity::Center }
+fn default_saturation()             -> f32          { 1.0 }
+fn default_hue_shift()              -> f32          { 0.0 }
+fn default_temperature()            -> f32          { 0.0 }
+fn default_sepia()                  -> f32          { 0.0 }
+fn default_tint_strength()          -> f32          { 0.0 }
+fn default_gradient_map()           -> Vec<[u8; 3]> { Vec::new() }
+fn default_filters()                -> Vec<ImageFilter> { Vec::new() }
+fn default_auto_orient()            -> bool         { true }
+fn default_crop()                   -> Option<CropRect> { None }
+fn default_rotate()                 -> u32          { 0 }
+fn default_flip()                   -> bool         { false }
+fn default_rotate_degrees()         -> f32          { 0.0 }
+fn default_filter_sigma()           -> f32          { 1.0 }
+fn default_unsharp_amount()         -> f32          { 0.8 }
+fn default_unsharp_threshold()      -> u8           { 2 }
+fn default_median_radius()          -> u32          { 1 }
+fn default_posterize_levels()       -> u8           { 4 }
+fn default_edge_strength()          -> f32          { 1.0 }
+
+// ===== Option Enums =====
+/// Color output of each cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    /// No color; `rgb` is always `None`.
+    #[default]
+    None,
+    /// Full 24-bit color.
+    Truecolor,
+    /// Nearest color of the xterm 256-color palette.
+    Xterm256,
+    /// Nearest of the 16 standard ANSI colors.
+    Ansi16,
+    /// Shades of `tint_color` scaled by each cell's intensity.
+    GrayscaleTint,
+    /// Nearest color of the user-supplied `palette`.
+    Palette,
+    /// Each cell's intensity mapped onto the `gradient_map` color ramp (two stops for duotone).
+    GradientMap,
+}
+
+/// Accepts either a `ColorMode` or the legacy `is_color` boolean.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColorModeOrFlag {
+    Mode(ColorMode),
+    Flag(bool),
+}
+
+/// Accepts either the name of a character set preset or an explicit list of glyphs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CharsetSpec {
+    Preset(String),
+    Glyphs(Vec<String>),
+}
+
+fn deserialize_charset<'de, D>(deserializer: D) -> Result<Vec<Glyph>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match CharsetSpec::deserialize(deserializer)? {
+        CharsetSpec::Glyphs(glyphs) => glyphs
+            .iter()
+            .map(|glyph| Glyph::parse(glyph).map_err(serde::de::Error::custom))
+            .collect(),
+        CharsetSpec::Preset(name) => charsets::lookup(&name).map(|preset| preset.glyphs()).ok_or_else(|| {
+            let names: Vec<&str> = charsets::PRESETS.iter().map(|preset| preset.name).collect();
+            serde::de::Error::custom(format!("unknown character set preset `{}`, expected one of: {}", name, names.join(", ")))
+        }),
+    }
+}
+
+fn deserialize_glyph<'de, D>(deserializer: D) -> Result<Glyph, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Glyph::parse(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_color_mode<'de, D>(deserializer: D) -> Result<ColorMode, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match ColorModeOrFlag::deserialize(deserializer)? {
+        ColorModeOrFlag::Mode(mode) => mode,
+        ColorModeOrFlag::Flag(true) => ColorMode::Truecolor,
+        ColorModeOrFlag::Flag(false) => ColorMode::None,
+    })
+}
+
+/// Dithering strategy used when quantizing intensities onto the character set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DitherMode {
+    /// Each cell is quantized independently.
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion.
+    FloydSteinberg,
+    /// Atkinson error diffusion (propagates 3/4 of the error, higher contrast).
+    Atkinson,
+    /// Ordered dithering with a 4x4 Bayer matrix.
+    Bayer,
+}
+
+/// How characters are chosen for each cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// Map each cell's intensity onto the character set.
+    #[default]
+    Ramp,
+    /// Draw strong edges with direction glyphs (`- / | \`), falling back to the ramp elsewhere.
+    Edges,
+    /// Compare each cell's pixel block against the rasterized glyphs and pick the closest shape.
+    GlyphMatch,
+    /// Upper/lower half blocks (`▀ ▄ █`): two sub-pixels per cell.
+    HalfBlock,
+    /// Quadrant blocks (`▘ ▝ ▖ ▗ ▚ ▞ ...`): 2x2 sub-pixels per cell.
+    Quadrant,
+    /// Sextant blocks from Symbols for Legacy Computing: 2x3 sub-pixels per cell.
+    Sextant,
+    /// Braille patterns (U+2800-U+28FF): a 2x4 dot matrix per cell.
+    Braille,
+    /// Emoji matched to each cell's average color. Every emoji spans two columns.
+    Emoji,
+    /// Two levels only: the first and last characters of the set, split by `threshold_method`.
+    Threshold,
+}
+
+/// Gradient operator used by `RenderMode::Edges`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeDetector {
+    /// Plain Sobel gradient magnitude.
+    #[default]
+    Sobel,
+    /// Canny: smoothed Sobel with non-maximum suppression and hysteresis, giving thin outlines.
+    Canny,
+}
+
+/// Resampling filter used when scaling the image to the output grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeFilter {
+    /// Nearest neighbour: fastest, aliases heavily on downscales.
+    Nearest,
+    /// Linear (tent) filter.
+    Triangle,
+    /// Cubic Catmull-Rom filter.
+    CatmullRom,
+    /// Gaussian filter: soft, little ringing.
+    Gaussian,
+    /// Lanczos with a window of 3: sharp.
+    #[default]
+    Lanczos3,
+    /// Box/area average over every source pixel covering a cell; best for large downscales.
+    Area,
+}
+
+/// Formula used to turn a cell's color into an intensity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LuminanceModel {
+    /// Rec. 601 luma weights applied directly to gamma-encoded sRGB values.
+    #[default]
+    Rec601,
+    /// Rec. 709 relative luminance computed in linear light, re-encoded with the sRGB curve.
+    Rec709,
+    /// CIE 1976 lightness (L*), perceptually uniform.
+    CieLightness,
+}
+
+/// Pixel channel that drives glyph selection. Colors always come from the pixel itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IntensitySource {
+    /// Luminance computed with `luminance_model`.
+    #[default]
+    Luminance,
+    /// CIE 1976 lightness (L*), regardless of `luminance_model`.
+    Lightness,
+    /// HSV value: the brightest of the three channels.
+    Value,
+    /// HSV saturation: vivid colors get dense glyphs, greys stay blank.
+    Saturation,
+    /// The red channel only.
+    Red,
+    /// The green channel only.
+    Green,
+    /// The blue channel only.
+    Blue,
+    /// Luminance flipped so dark pixels get dense glyphs, for light backgrounds.
+    InvertedLuminance,
+}
+
+/// Background the output is shown on, which decides whether bright or dark pixels get dense glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTheme {
+    /// Dark background: bright pixels get dense glyphs.
+    #[default]
+    Dark,
+    /// Light background: the ramp is flipped and light colors are darkened to stay legible.
+    Light,
+    /// `light` for images that are bright on average, `dark` otherwise.
+    Auto,
+}
+
+/// How the background color of each cell is generated in color mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CellBackground {
+    /// No background; the cell color is used for the glyph.
+    #[default]
+    None,
+    /// The cell's average color becomes the background and the glyph gets a contrasting foreground.
+    Average,
+}
+
+/// Automatic contrast correction applied to cell intensities after resizing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoContrast {
+    /// Intensities are used as they are.
+    #[default]
+    None,
+    /// Global histogram equalization: spreads intensities so every level is equally used.
+    Equalize,
+    /// Stretches the range between the low and high percentiles to full black and white.
+    AutoLevels,
+    /// Contrast-limited adaptive histogram equalization over a grid of tiles.
+    Clahe,
+}
+
+/// How transparent regions of the source image are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AlphaMode {
+    /// Alpha is dropped and the stored color of transparent pixels is used as is.
+    #[default]
+    Ignore,
+    /// The image is composited onto `alpha_background` before conversion.
+    Composite,
+    /// Composited like `composite`, and cells below `alpha_threshold` become a blank space without color.
+    Blank,
+    /// Composited like `composite`, and each cell reports its opacity in `AsciiPixel::alpha`.
+    Keep,
+}
+
+/// Error metric used by `RenderMode::GlyphMatch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GlyphMetric {
+    /// Mean squared error: balances brightness and shape, robust on smooth gradients.
+    #[default]
+    Mse,
+    /// Structural similarity: favours matching shape and contrast, sharpest on text and diagrams.
+    Ssim,
+}
+
+/// How the character set is calibrated against the measured ink coverage of its glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DensityCalibration {
+    /// Characters are assumed to be evenly spaced in darkness.
+    #[default]
+    None,
+    /// Keep the given order, but map each intensity to the glyph whose measured density is closest.
+    Remap,
+    /// Sort the characters by measured density and drop glyphs that are indistinguishable, then remap.
+    Sort,
+}
+
+/// Tone curve applied to intensities after auto-contrast, before characters are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneCurve {
+    /// Intensities are used as is.
+    #[default]
+    None,
+    /// Power curve with exponent `1 / gamma`.
+    Gamma,
+    /// Smooth S-shaped contrast curve scaled by `s_curve_strength`.
+    SCurve,
+    /// Monotone curve through the `curve_points` control points.
+    Points,
+}
+
+/// How `RenderMode::Threshold` splits cells into dark and bright.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdMethod {
+    /// Cells at or above `binary_threshold` are bright.
+    #[default]
+    Fixed,
+    /// Otsu's method picks the threshold that best separates the image's histogram.
+    Otsu,
+    /// Each cell is compared with the mean of its `adaptive_window` neighbourhood minus `adaptive_offset`.
+    Adaptive,
+}
+
+/// How the image is fitted into the `output_width` x `output_height` box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FitMode {
+    /// Scale each axis to fill the box, distorting the image if the aspect ratios differ.
+    #[default]
+    Stretch,
+    /// Scale the whole image into the box and fill the rest with `padding_char`.
+    Contain,
+    /// Scale the image to cover the box and crop the overflow, keeping the side at `gravity`.
+    Cover,
+}
+
+/// Anchor of the image for `fit: contain` padding and `fit: cover` cropping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Gravity {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// An emoji and the color it represents in `RenderMode::Emoji`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmojiColor {
+    #[serde(deserialize_with = "deserialize_glyph")]
+    pub emoji: Glyph,
+    pub rgb: [u8; 3],
+}
+
+// ===== Preprocessing =====
+/// Rectangle in source pixels, measured after EXIF orientation is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Geometric operations applied to the source image before resizing, in field order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreprocessConfig {
+    /// Rotate/flip the image according to its EXIF orientation tag.
+    #[serde(default = "default_auto_orient")]
+    pub auto_orient: bool,
+
+    /// Optional crop rectangle.
+    #[serde(default = "default_crop")]
+    pub crop: Option<CropRect>,
+
+    /// Clockwise rotation in quarter turns: 0, 90, 180 or 270 degrees.
+    #[serde(default = "default_rotate")]
+    pub rotate: u32,
+
+    /// Mirror the image left to right.
+    #[serde(default = "default_flip")]
+    pub flip_horizontal: bool,
+
+    /// Mirror the image top to bottom.
+    #[serde(default = "default_flip")]
+    pub flip_vertical: bool,
+
+    /// Additional clockwise rotation by any angle. The canvas grows to fit and the
+    /// uncovered corners are transparent, so they follow `alpha_mode`.
+    #[serde(default = "default_rotate_degrees")]
+    pub rotate_degrees: f32,
+}
+
+// ===== Filters =====
+/// When an image filter runs relative to the resize to the output resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterStage {
+    /// On the source image, after preprocessing and alpha handling.
+    #[default]
+    BeforeResize,
+    /// On the resized image, just before characters are chosen.
+    AfterResize,
+}
+
+/// An image filter and its parameters, selected by `type`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterKind {
+    /// Sharpen by adding back `amount` times the detail removed by a Gaussian blur of `sigma`.
+    /// Channel differences up to `threshold` are ignored, so noise is not amplified.
+    UnsharpMask {
+        #[serde(default = "default_filter_sigma")]
+        sigma: f32,
+        #[serde(default = "default_unsharp_amount")]
+        amount: f32,
+        #[serde(default = "default_unsharp_threshold")]
+        threshold: u8,
+    },
+    /// Gaussian blur with standard deviation `sigma` (in pixels).
+    GaussianBlur {
+        #[serde(default = "default_filter_sigma")]
+        sigma: f32,
+    },
+    /// Median of the square window of `radius` (1-5) around each pixel; removes speckle noise.
+    Median {
+        #[serde(default = "default_median_radius")]
+        radius: u32,
+    },
+    /// Reduce every channel to `levels` (at least 2) evenly spaced values.
+    Posterize {
+        #[serde(default = "default_posterize_levels")]
+        levels: u8,
+    },
+    /// Boost differences between each pixel and its neighbours; 1.0 is the classic 3x3 kernel.
+    EdgeEnhance {
+        #[serde(default = "default_edge_strength")]
+        strength: f32,
+    },
+}
+
+/// One entry of the `filters` list.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImageFilter {
+    #[serde(flatten)]
+    pub kind: FilterKind,
+    #[serde(default)]
+    pub stage: FilterStage,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            auto_orient: default_auto_orient(),
+            crop: default_crop(),
+            rotate: default_rotate(),
+            flip_horizontal: default_flip(),
+            flip_vertical: default_flip(),
+            rotate_degrees: default_rotate_degrees(),
+        }
+    }
+}
 
 // ===== Configuration Struct =====
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConverterConfig {
-    /// Character set for ASCII representation (dark to light).
-    #[serde(default = "default_charset")]
-    pub character_set: Vec<char>,
+    /// Character set for ASCII representation (dark to light): a preset name or an explicit array.
+    /// Each entry is one grapheme cluster; wide glyphs (CJK, emoji) take two columns.
+    #[serde(default = "default_charset", deserialize_with = "deserialize_charset")]
+    pub character_set: Vec<Glyph>,
 
     /// Width of the output ASCII art (in characters).
     #[serde(default = "default_output_width")]
@@ -32,13 +525,301 @@ pub struct ConverterConfig {
     #[serde(default = "default_contrast")]
     pub contrast_factor: f32,
 
-    /// Whether to include color in the output.
-    #[serde(default = "default_is_color")]
-    pub is_color: bool,
+    /// Color output mode. The legacy `is_color` boolean is accepted as an alias
+    /// (`true` = `truecolor`, `false` = `none`).
+    #[serde(default = "default_color_mode", alias = "is_color", deserialize_with = "deserialize_color_mode")]
+    pub color_mode: ColorMode,
 
     /// Aspect ratio correction factor (default 0.55 for ASCII art).
     #[serde(default = "default_aspect_ratio_correction")]
     pub aspect_ratio_correction: f32,
+
+    /// Dithering applied when mapping intensities to characters.
+    #[serde(default = "default_dither")]
+    pub dither: DitherMode,
+
+    /// Character selection mode.
+    #[serde(default = "default_render_mode")]
+    pub render_mode: RenderMode,
+
+    /// Edge detector used when `render_mode` is `edges`.
+    #[serde(default = "default_edge_detector")]
+    pub edge_detector: EdgeDetector,
+
+    /// Minimum gradient magnitude (as a 0-255 intensity step) for a cell to be drawn as an edge.
+    #[serde(default = "default_edge_threshold")]
+    pub edge_threshold: f32,
+
+    /// Similarity metric used when `render_mode` is `glyph_match`.
+    #[serde(default = "default_glyph_metric")]
+    pub glyph_metric: GlyphMetric,
+
+    /// Intensity (0-255) at or above which a Braille dot is raised.
+    #[serde(default = "default_braille_threshold")]
+    pub braille_threshold: u8,
+
+    /// Background color generation for each cell (requires a `color_mode` other than `none`).
+    #[serde(default = "default_cell_background")]
+    pub cell_background: CellBackground,
+
+    /// Resampling filter used when resizing the image to the output resolution.
+    #[serde(default = "default_resize_filter")]
+    pub resize_filter: ResizeFilter,
+
+    /// Model used to compute intensity from color.
+    #[serde(default = "default_luminance_model")]
+    pub luminance_model: LuminanceModel,
+
+    /// Channel whose value picks each cell's glyph.
+    #[serde(default = "default_intensity_source")]
+    pub intensity_source: IntensitySource,
+
+    /// Background the output will be shown on.
+    #[serde(default = "default_background_theme")]
+    pub background_theme: BackgroundTheme,
+
+    /// Resize in linear light (decode sRGB first, re-encode after) instead of on gamma-encoded values.
+    #[serde(default = "default_linear_light")]
+    pub linear_light: bool,
+
+    /// Automatic contrast correction applied to intensities before choosing characters.
+    #[serde(default = "default_auto_contrast")]
+    pub auto_contrast: AutoContrast,
+
+    /// Percentage of cells clipped at each end of the range by `auto_levels`.
+    #[serde(default = "default_auto_levels_percentile")]
+    pub auto_levels_percentile: f32,
+
+    /// CLAHE clip limit as a multiple of the mean histogram bin height (higher = more contrast).
+    #[serde(default = "default_clahe_clip_limit")]
+    pub clahe_clip_limit: f32,
+
+    /// Number of CLAHE tiles along each axis.
+    #[serde(default = "default_clahe_tiles")]
+    pub clahe_tiles: u32,
+
+    /// Handling of transparent pixels.
+    #[serde(default = "default_alpha_mode")]
+    pub alpha_mode: AlphaMode,
+
+    /// Color transparent pixels are composited onto (unless `alpha_mode` is `ignore`).
+    #[serde(default = "default_alpha_background")]
+    pub alpha_background: [u8; 3],
+
+    /// Cell opacity (0-255) below which `alpha_mode: blank` leaves the cell empty.
+    #[serde(default = "default_alpha_threshold")]
+    pub alpha_threshold: u8,
+
+    /// Colors used by `color_mode: palette`.
+    #[serde(default = "default_palette")]
+    pub palette: Vec<[u8; 3]>,
+
+    /// Color used by `color_mode: grayscale_tint` and `tint_strength`.
+    #[serde(default = "default_tint_color")]
+    pub tint_color: [u8; 3],
+
+    /// Color saturation: 0 is grayscale, 1 leaves colors unchanged, above 1 boosts them.
+    #[serde(default = "default_saturation")]
+    pub saturation: f32,
+
+    /// Hue rotation in degrees.
+    #[serde(default = "default_hue_shift")]
+    pub hue_shift: f32,
+
+    /// Color temperature from -1 (cool) to 1 (warm).
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+
+    /// Amount (0-1) of sepia toning.
+    #[serde(default = "default_sepia")]
+    pub sepia: f32,
+
+    /// Amount (0-1) of `tint_color` blended into every color.
+    #[serde(default = "default_tint_strength")]
+    pub tint_strength: f32,
+
+    /// Color stops of `color_mode: gradient_map`, evenly spaced from dark to bright.
+    #[serde(default = "default_gradient_map")]
+    pub gradient_map: Vec<[u8; 3]>,
+
+    /// Dithering applied when quantizing colors onto a palette.
+    #[serde(default = "default_color_dither")]
+    pub color_dither: DitherMode,
+
+    /// Orientation, crop, rotation and flips applied before resizing.
+    #[serde(default = "default_preprocess")]
+    pub preprocess: PreprocessConfig,
+
+    /// How the image is fitted into the output box.
+    #[serde(default = "default_fit")]
+    pub fit: FitMode,
+
+    /// Upper bound on the output width in cells; the output shrinks, keeping its proportions.
+    #[serde(default = "default_max_size")]
+    pub max_width: Option<u32>,
+
+    /// Upper bound on the output height in rows; the output shrinks, keeping its proportions.
+    #[serde(default = "default_max_size")]
+    pub max_height: Option<u32>,
+
+    /// Never scale the image above one source pixel per cell.
+    #[serde(default = "default_no_upscale")]
+    pub no_upscale: bool,
+
+    /// Glyph for the padding cells of `fit: contain`.
+    #[serde(default = "default_padding_char", deserialize_with = "deserialize_glyph")]
+    pub padding_char: Glyph,
+
+    /// Where the image is anchored when it is padded or cropped.
+    #[serde(default = "default_gravity")]
+    pub gravity: Gravity,
+
+    /// Total character budget of the output as text, including newlines. When set, the
+    /// largest size up to `output_width` that fits is chosen, keeping the aspect ratio.
+    #[serde(default = "default_max_chars")]
+    pub max_chars: Option<usize>,
+
+    /// Count ANSI color escape sequences against `max_chars`.
+    #[serde(default = "default_count_ansi")]
+    pub count_ansi: bool,
+
+    /// Image filters (sharpen, blur, denoise, posterize, edge enhance), run in list order
+    /// before or after the resize.
+    #[serde(default = "default_filters")]
+    pub filters: Vec<ImageFilter>,
+
+    /// Maximum number of frames converted from an animation; later frames are dropped.
+    #[serde(default = "default_max_frames")]
+    pub max_frames: u32,
+
+    /// Calibration of the character set against measured glyph densities (ramp and edge modes).
+    #[serde(default = "default_density_calibration")]
+    pub density_calibration: DensityCalibration,
+
+    /// TTF/OTF font measured by `density_calibration` instead of the bundled bitmap font.
+    /// Uploaded as the multipart `font` field rather than in the JSON config.
+    #[serde(skip)]
+    pub calibration_font: Option<Vec<u8>>,
+
+    /// Tone curve applied to intensities (after auto-contrast) before characters are chosen.
+    #[serde(default = "default_tone_curve")]
+    pub tone_curve: ToneCurve,
+
+    /// Gamma used by `tone_curve: gamma` (values above 1 brighten the midtones).
+    #[serde(default = "default_gamma")]
+    pub gamma: f32,
+
+    /// Strength (-1 to 1) of `tone_curve: s_curve`; negative values reduce contrast.
+    #[serde(default = "default_s_curve_strength")]
+    pub s_curve_strength: f32,
+
+    /// `[input, output]` control points (0-255) of `tone_curve: points`, ordered by input.
+    #[serde(default = "default_curve_points")]
+    pub curve_points: Vec<[u8; 2]>,
+
+    /// Intensity (0-255) at which each character of `character_set` starts, in ascending
+    /// order. Empty means evenly sized ranges.
+    #[serde(default = "default_char_thresholds")]
+    pub char_thresholds: Vec<u8>,
+
+    /// Extra emoji for `render_mode: emoji`, preferred over built-in emoji of the same color.
+    #[serde(default = "default_emoji_table")]
+    pub emoji_table: Vec<EmojiColor>,
+
+    /// Include the built-in emoji table in `render_mode: emoji`.
+    #[serde(default = "default_emoji_builtin")]
+    pub emoji_builtin: bool,
+
+    /// How `render_mode: threshold` chooses between the two characters.
+    #[serde(default = "default_threshold_method")]
+    pub threshold_method: ThresholdMethod,
+
+    /// Intensity (0-255) at or above which a cell is bright with `threshold_method: fixed`.
+    #[serde(default = "default_binary_threshold")]
+    pub binary_threshold: u8,
+
+    /// Side length, in cells, of the neighbourhood averaged by `threshold_method: adaptive`.
+    #[serde(default = "default_adaptive_window")]
+    pub adaptive_window: u32,
+
+    /// Amount subtracted from the local mean by `threshold_method: adaptive`; higher values
+    /// keep flat regions bright.
+    #[serde(default = "default_adaptive_offset")]
+    pub adaptive_offset: f32,
+}
+
+impl Default for ConverterConfig {
+    /// The same values as deserializing `{}`.
+    fn default() -> Self {
+        Self {
+            character_set: default_charset(),
+            output_width: default_output_width(),
+            output_height: default_output_height(),
+            brightness_factor: default_brightness(),
+            contrast_factor: default_contrast(),
+            color_mode: default_color_mode(),
+            aspect_ratio_correction: default_aspect_ratio_correction(),
+            dither: default_dither(),
+            render_mode: default_render_mode(),
+            edge_detector: default_edge_detector(),
+            edge_threshold: default_edge_threshold(),
+            glyph_metric: default_glyph_metric(),
+            braille_threshold: default_braille_threshold(),
+            cell_background: default_cell_background(),
+            resize_filter: default_resize_filter(),
+            luminance_model: default_luminance_model(),
+            intensity_source: default_intensity_source(),
+            background_theme: default_background_theme(),
+            linear_light: default_linear_light(),
+            auto_contrast: default_auto_contrast(),
+            auto_levels_percentile: default_auto_levels_percentile(),
+            clahe_clip_limit: default_clahe_clip_limit(),
+            clahe_tiles: default_clahe_tiles(),
+            alpha_mode: default_alpha_mode(),
+            alpha_background: default_alpha_background(),
+            alpha_threshold: default_alpha_threshold(),
+            palette: default_palette(),
+            tint_color: default_tint_color(),
+            saturation: default_saturation(),
+            hue_shift: default_hue_shift(),
+            temperature: default_temperature(),
+            sepia: default_sepia(),
+            tint_strength: default_tint_strength(),
+            gradient_map: default_gradient_map(),
+            color_dither: default_color_dither(),
+            preprocess: default_preprocess(),
+            fit: default_fit(),
+            max_width: default_max_size(),
+            max_height: default_max_size(),
+            no_upscale: default_no_upscale(),
+            padding_char: default_padding_char(),
+            gravity: default_gravity(),
+            max_chars: default_max_chars(),
+            count_ansi: default_count_ansi(),
+            filters: default_filters(),
+            max_frames: default_max_frames(),
+            density_calibration: default_density_calibration(),
+            calibration_font: None,
+            tone_curve: default_tone_curve(),
+            gamma: default_gamma(),
+            s_curve_strength: default_s_curve_strength(),
+            curve_points: default_curve_points(),
+            char_thresholds: default_char_thresholds(),
+            emoji_table: default_emoji_table(),
+            emoji_builtin: default_emoji_builtin(),
+            threshold_method: default_threshold_method(),
+            binary_threshold: default_binary_threshold(),
+            adaptive_window: default_adaptive_window(),
+            adaptive_offset: default_adaptive_offset(),
+        }
+    }
+}
+
+impl ConverterConfig {
+    /// Whether cells carry a color.
+    pub fn is_color(&self) -> bool {
+        self.color_mode != ColorMode::None
+    }
 }
 
 // ===== Configuration Struct Tests =====
@@ -56,8 +837,67 @@ mod tests {
         assert_eq!(config.output_height, default_output_height());
         assert_eq!(config.brightness_factor, default_brightness());
         assert_eq!(config.contrast_factor, default_contrast());
-        assert_eq!(config.is_color, default_is_color());
+        assert_eq!(config.color_mode, default_color_mode());
         assert_eq!(config.aspect_ratio_correction, default_aspect_ratio_correction());
+        assert_eq!(config.dither, default_dither());
+        assert_eq!(config.render_mode, default_render_mode());
+        assert_eq!(config.edge_detector, default_edge_detector());
+        assert_eq!(config.edge_threshold, default_edge_threshold());
+        assert_eq!(config.glyph_metric, default_glyph_metric());
+        assert_eq!(config.braille_threshold, default_braille_threshold());
+        assert_eq!(config.cell_background, default_cell_background());
+        assert_eq!(config.resize_filter, default_resize_filter());
+        assert_eq!(config.luminance_model, default_luminance_model());
+        assert_eq!(config.intensity_source, default_intensity_source());
+        assert_eq!(config.background_theme, default_background_theme());
+        assert_eq!(config.linear_light, default_linear_light());
+        assert_eq!(config.auto_contrast, default_auto_contrast());
+        assert_eq!(config.auto_levels_percentile, default_auto_levels_percentile());
+        assert_eq!(config.clahe_clip_limit, default_clahe_clip_limit());
+        assert_eq!(config.clahe_tiles, default_clahe_tiles());
+        assert_eq!(config.alpha_mode, default_alpha_mode());
+        assert_eq!(config.alpha_background, default_alpha_background());
+        assert_eq!(config.alpha_threshold, default_alpha_threshold());
+        assert_eq!(config.palette, default_palette());
+        assert_eq!(config.tint_color, default_tint_color());
+        assert_eq!(config.saturation, default_saturation());
+        assert_eq!(config.hue_shift, default_hue_shift());
+        assert_eq!(config.temperature, default_temperature());
+        assert_eq!(config.sepia, default_sepia());
+        assert_eq!(config.tint_strength, default_tint_strength());
+        assert_eq!(config.gradient_map, default_gradient_map());
+        assert_eq!(config.color_dither, default_color_dither());
+        assert_eq!(config.preprocess, default_preprocess());
+        assert!(config.preprocess.auto_orient);
+        assert_eq!(config.filters, default_filters());
+        assert_eq!(config.fit, default_fit());
+        assert_eq!(config.max_width, default_max_size());
+        assert_eq!(config.max_height, default_max_size());
+        assert_eq!(config.no_upscale, default_no_upscale());
+        assert_eq!(config.padding_char, default_padding_char());
+        assert_eq!(config.gravity, default_gravity());
+        assert_eq!(config.max_chars, default_max_chars());
+        assert_eq!(config.count_ansi, default_count_ansi());
+        assert_eq!(config.max_frames, default_max_frames());
+        assert_eq!(config.density_calibration, default_density_calibration());
+        assert!(config.calibration_font.is_none());
+        assert_eq!(config.tone_curve, default_tone_curve());
+        assert_eq!(config.gamma, default_gamma());
+        assert_eq!(config.s_curve_strength, default_s_curve_strength());
+        assert_eq!(config.curve_points, default_curve_points());
+        assert_eq!(config.char_thresholds, default_char_thresholds());
+        assert_eq!(config.emoji_table, default_emoji_table());
+        assert_eq!(config.emoji_builtin, default_emoji_builtin());
+        assert_eq!(config.threshold_method, default_threshold_method());
+        assert_eq!(config.binary_threshold, default_binary_threshold());
+        assert_eq!(config.adaptive_window, default_adaptive_window());
+        assert_eq!(config.adaptive_offset, default_adaptive_offset());
+    }
+
+    #[test]
+    fn test_default_matches_empty_json() {
+        let config: ConverterConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(serde_json::to_value(ConverterConfig::default()).unwrap(), serde_json::to_value(config).unwrap());
     }
 
     #[test]
@@ -69,7 +909,7 @@ mod tests {
         let config: ConverterConfig = serde_json::from_value(json).unwrap();
         // Check that partial deserialization is correct
         assert_eq!(config.output_width, 80);
-        assert_eq!(config.is_color, true);
+        assert_eq!(config.color_mode, ColorMode::Truecolor);
 
         // Check that defaults are still set for other fields
         assert_eq!(config.character_set, default_charset());
@@ -81,22 +921,206 @@ mod tests {
 
     #[test]
     fn test_full_deserialize() {
-        let json = json!({
+        // A string rather than `json!`, which runs out of macro recursion at this size
+        let json = r##"{
             "character_set": ["#", "."],
             "output_width": 100,
             "output_height": 50,
             "brightness_factor": 2.0,
             "contrast_factor": 0.5,
-            "is_color": true,
-            "aspect_ratio_correction": 1.0
-        });
-        let config: ConverterConfig = serde_json::from_value(json).unwrap();
+            "color_mode": "palette",
+            "aspect_ratio_correction": 1.0,
+            "dither": "floyd_steinberg",
+            "render_mode": "quadrant",
+            "edge_detector": "canny",
+            "edge_threshold": 30.0,
+            "glyph_metric": "ssim",
+            "braille_threshold": 100,
+            "cell_background": "average",
+            "resize_filter": "area",
+            "luminance_model": "cie_lightness",
+            "intensity_source": "saturation",
+            "background_theme": "light",
+            "linear_light": true,
+            "auto_contrast": "clahe",
+            "auto_levels_percentile": 0.5,
+            "clahe_clip_limit": 3.0,
+            "clahe_tiles": 4,
+            "alpha_mode": "blank",
+            "alpha_background": [255, 255, 255],
+            "alpha_threshold": 64,
+            "palette": [[0, 0, 0], [255, 128, 0]],
+            "tint_color": [255, 176, 0],
+            "color_dither": "atkinson",
+            "preprocess": {
+                "auto_orient": false,
+                "crop": { "x": 10, "y": 20, "width": 300, "height": 200 },
+                "rotate": 90,
+                "flip_horizontal": true,
+                "flip_vertical": true,
+                "rotate_degrees": 12.5
+            },
+            "max_frames": 24,
+            "density_calibration": "sort",
+            "tone_curve": "points",
+            "gamma": 2.2,
+            "s_curve_strength": -0.5,
+            "curve_points": [[0, 0], [128, 160], [255, 255]],
+            "char_thresholds": [0, 100],
+            "emoji_table": [{ "emoji": "🔥", "rgb": [244, 144, 12] }],
+            "emoji_builtin": false,
+            "threshold_method": "adaptive",
+            "binary_threshold": 90,
+            "adaptive_window": 9,
+            "adaptive_offset": 4.0
+        }"##;
+        let config: ConverterConfig = serde_json::from_str(json).unwrap();
         assert_eq!(config.character_set, vec!['#', '.']);
         assert_eq!(config.output_width, 100);
         assert_eq!(config.output_height, Some(50));
         assert_eq!(config.brightness_factor, 2.0);
         assert_eq!(config.contrast_factor, 0.5);
-        assert_eq!(config.is_color, true);
+        assert_eq!(config.color_mode, ColorMode::Palette);
         assert_eq!(config.aspect_ratio_correction, 1.0);
+        assert_eq!(config.dither, DitherMode::FloydSteinberg);
+        assert_eq!(config.render_mode, RenderMode::Quadrant);
+        assert_eq!(config.edge_detector, EdgeDetector::Canny);
+        assert_eq!(config.edge_threshold, 30.0);
+        assert_eq!(config.glyph_metric, GlyphMetric::Ssim);
+        assert_eq!(config.braille_threshold, 100);
+        assert_eq!(config.cell_background, CellBackground::Average);
+        assert_eq!(config.resize_filter, ResizeFilter::Area);
+        assert_eq!(config.luminance_model, LuminanceModel::CieLightness);
+        assert_eq!(config.intensity_source, IntensitySource::Saturation);
+        assert_eq!(config.background_theme, BackgroundTheme::Light);
+        assert!(config.linear_light);
+        assert_eq!(config.auto_contrast, AutoContrast::Clahe);
+        assert_eq!(config.auto_levels_percentile, 0.5);
+        assert_eq!(config.clahe_clip_limit, 3.0);
+        assert_eq!(config.clahe_tiles, 4);
+        assert_eq!(config.alpha_mode, AlphaMode::Blank);
+        assert_eq!(config.alpha_background, [255, 255, 255]);
+        assert_eq!(config.alpha_threshold, 64);
+        assert_eq!(config.palette, vec![[0, 0, 0], [255, 128, 0]]);
+        assert_eq!(config.tint_color, [255, 176, 0]);
+        assert_eq!(config.color_dither, DitherMode::Atkinson);
+        assert!(!config.preprocess.auto_orient);
+        assert_eq!(config.preprocess.crop, Some(CropRect { x: 10, y: 20, width: 300, height: 200 }));
+        assert_eq!(config.preprocess.rotate, 90);
+        assert!(config.preprocess.flip_horizontal);
+        assert!(config.preprocess.flip_vertical);
+        assert_eq!(config.preprocess.rotate_degrees, 12.5);
+        assert_eq!(config.max_frames, 24);
+        assert_eq!(config.density_calibration, DensityCalibration::Sort);
+        assert_eq!(config.tone_curve, ToneCurve::Points);
+        assert_eq!(config.gamma, 2.2);
+        assert_eq!(config.s_curve_strength, -0.5);
+        assert_eq!(config.curve_points, vec![[0, 0], [128, 160], [255, 255]]);
+        assert_eq!(config.char_thresholds, vec![0, 100]);
+        assert_eq!(config.emoji_table, vec![EmojiColor { emoji: Glyph::new("🔥"), rgb: [244, 144, 12] }]);
+        assert!(!config.emoji_builtin);
+        assert_eq!(config.threshold_method, ThresholdMethod::Adaptive);
+        assert_eq!(config.binary_threshold, 90);
+        assert_eq!(config.adaptive_window, 9);
+        assert_eq!(config.adaptive_offset, 4.0);
+    }
+
+    #[test]
+    fn test_charset_presets() {
+        let config: ConverterConfig = serde_json::from_value(json!({ "character_set": "blocks" })).unwrap();
+        assert_eq!(config.character_set, vec![' ', '░', '▒', '▓', '█']);
+        assert_eq!(charsets::lookup("standard").unwrap().glyphs(), default_charset());
+
+        let error = serde_json::from_value::<ConverterConfig>(json!({ "character_set": "nope" })).unwrap_err();
+        assert!(error.to_string().contains("unknown character set preset"));
+    }
+
+    #[test]
+    fn test_grapheme_cluster_charset() {
+        let config: ConverterConfig = serde_json::from_value(json!({ "character_set": [" ", "e\u{301}", "漢", "👍🏽"] })).unwrap();
+        assert_eq!(config.character_set.len(), 4);
+        assert_eq!(config.character_set[3].width(), 2);
+
+        let error = serde_json::from_value::<ConverterConfig>(json!({ "character_set": [" ", "ab"] })).unwrap_err();
+        assert!(error.to_string().contains("grapheme cluster"));
+    }
+
+    #[test]
+    fn test_color_grading() {
+        let json = json!({
+            "color_mode": "gradient_map",
+            "gradient_map": [[20, 0, 80], [255, 64, 128], [255, 240, 200]],
+            "saturation": 1.4,
+            "hue_shift": -30.0,
+            "temperature": 0.5,
+            "sepia": 0.25,
+            "tint_strength": 0.1
+        });
+        let config: ConverterConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.color_mode, ColorMode::GradientMap);
+        assert!(config.is_color());
+        assert_eq!(config.gradient_map.len(), 3);
+        assert_eq!(config.saturation, 1.4);
+        assert_eq!(config.hue_shift, -30.0);
+        assert_eq!(config.temperature, 0.5);
+        assert_eq!(config.sepia, 0.25);
+        assert_eq!(config.tint_strength, 0.1);
+    }
+
+    #[test]
+    fn test_fit_options() {
+        let json = json!({
+            "fit": "contain",
+            "max_width": 120,
+            "max_height": 40,
+            "no_upscale": true,
+            "padding_char": "·",
+            "gravity": "bottom_right",
+            "max_chars": 2000,
+            "count_ansi": true
+        });
+        let config: ConverterConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.fit, FitMode::Contain);
+        assert_eq!((config.max_width, config.max_height), (Some(120), Some(40)));
+        assert!(config.no_upscale);
+        assert_eq!(config.padding_char, '·');
+        assert_eq!(config.gravity, Gravity::BottomRight);
+        assert_eq!(config.max_chars, Some(2000));
+        assert!(config.count_ansi);
+
+        let error = serde_json::from_value::<ConverterConfig>(json!({ "padding_char": "ab" })).unwrap_err();
+        assert!(error.to_string().contains("grapheme cluster"));
+    }
+
+    #[test]
+    fn test_filters() {
+        let json = json!({
+            "filters": [
+                { "type": "median" },
+                { "type": "unsharp_mask", "amount": 1.5, "stage": "after_resize" },
+                { "type": "posterize", "levels": 8 }
+            ]
+        });
+        let config: ConverterConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.filters, vec![
+            ImageFilter { kind: FilterKind::Median { radius: 1 }, stage: FilterStage::BeforeResize },
+            ImageFilter {
+                kind: FilterKind::UnsharpMask { sigma: 1.0, amount: 1.5, threshold: 2 },
+                stage: FilterStage::AfterResize,
+            },
+            ImageFilter { kind: FilterKind::Posterize { levels: 8 }, stage: FilterStage::BeforeResize },
+        ]);
+
+        let error = serde_json::from_value::<ConverterConfig>(json!({ "filters": [{ "type": "emboss" }] })).unwrap_err();
+        assert!(error.to_string().contains("unknown variant"));
+    }
+
+    #[test]
+    fn test_color_mode_accepts_legacy_flag() {
+        let config: ConverterConfig = serde_json::from_value(json!({ "is_color": false })).unwrap();
+        assert_eq!(config.color_mode, ColorMode::None);
+        let config: ConverterConfig = serde_json::from_value(json!({ "color_mode": "xterm256" })).unwrap();
+        assert_eq!(config.color_mode, ColorMode::Xterm256);
+        assert!(config.is_color());
     }
 }
\ No newline at end of file
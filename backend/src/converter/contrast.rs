@@ -0,0 +1,204 @@
+use crate::converter::config::AutoContrast;
+
+/// Number of intensity levels handled by the histogram operations.
+const LEVELS: usize = 256;
+
+/// Smallest CLAHE tile edge, in cells. Smaller tiles have too few samples for a
+/// meaningful histogram and turn into noise.
+const MIN_TILE_SIZE: usize = 8;
+
+/// Tuning parameters for the automatic contrast operations.
+#[derive(Debug, Clone, Copy)]
+pub struct ContrastSettings {
+    /// Percentage of cells clipped at each end by auto-levels.
+    pub percentile: f32,
+    /// CLAHE clip limit, as a multiple of the average histogram bin height.
+    pub clip_limit: f32,
+    /// Number of CLAHE tiles along each axis.
+    pub tiles: u32,
+}
+
+/// Applies an automatic contrast operation in place to a row-major intensity grid.
+pub fn apply(values: &mut [u8], width: usize, height: usize, mode: AutoContrast, settings: ContrastSettings) {
+    debug_assert_eq!(values.len(), width * height);
+    if values.is_empty() {
+        return;
+    }
+
+    match mode {
+        AutoContrast::None => {}
+        AutoContrast::Equalize => {
+            let lut = equalization_lut(&histogram(values.iter().copied()));
+            values.iter_mut().for_each(|v| *v = lut[*v as usize]);
+        }
+        AutoContrast::AutoLevels => auto_levels(values, settings.percentile),
+        AutoContrast::Clahe => clahe(values, width, height, settings.clip_limit, settings.tiles),
+    }
+}
+
+/// Counts how many values fall in each intensity level.
+fn histogram(values: impl Iterator<Item = u8>) -> [f32; LEVELS] {
+    let mut histogram = [0.0f32; LEVELS];
+    for v in values {
+        histogram[v as usize] += 1.0;
+    }
+    histogram
+}
+
+/// Builds a lookup table that maps intensities through the normalized cumulative histogram.
+fn equalization_lut(histogram: &[f32; LEVELS]) -> [u8; LEVELS] {
+    let total: f32 = histogram.iter().sum();
+    let cdf_min = histogram.iter().copied().find(|&count| count > 0.0).unwrap_or(0.0);
+    let mut lut = [0u8; LEVELS];
+    if total - cdf_min <= f32::EPSILON * total {
+        // Single intensity: nothing to spread, keep values as they are
+        for (i, entry) in lut.iter_mut().enumerate() {
+            *entry = i as u8;
+        }
+        return lut;
+    }
+
+    let mut cumulative = 0.0;
+    for (entry, &count) in lut.iter_mut().zip(histogram) {
+        cumulative += count;
+        let scaled = (cumulative - cdf_min).max(0.0) / (total - cdf_min);
+        *entry = (scaled * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+/// Stretches the range between the low and high percentiles to the full 0-255 range.
+fn auto_levels(values: &mut [u8], percentile: f32) {
+    let histogram = histogram(values.iter().copied());
+    let clip = (values.len() as f32 * percentile.clamp(0.0, 49.0) / 100.0).floor();
+
+    let find = |levels: &mut dyn Iterator<Item = usize>| {
+        let mut seen = 0.0;
+        for level in levels {
+            seen += histogram[level];
+            if seen > clip {
+                return level;
+            }
+        }
+        0
+    };
+    let low = find(&mut (0..LEVELS));
+    let high = find(&mut (0..LEVELS).rev());
+    if high <= low {
+        return;
+    }
+
+    let scale = 255.0 / (high - low) as f32;
+    for v in values.iter_mut() {
+        *v = ((*v as f32 - low as f32) * scale).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Contrast-limited adaptive histogram equalization. Each tile gets its own clipped
+/// equalization curve; cells blend the curves of the four nearest tile centres.
+fn clahe(values: &mut [u8], width: usize, height: usize, clip_limit: f32, tiles: u32) {
+    let tiles_x = (tiles as usize).min(width / MIN_TILE_SIZE).max(1);
+    let tiles_y = (tiles as usize).min(height / MIN_TILE_SIZE).max(1);
+    let tile_width = width as f32 / tiles_x as f32;
+    let tile_height = height as f32 / tiles_y as f32;
+
+    // One lookup table per tile
+    let mut luts = Vec::with_capacity(tiles_x * tiles_y);
+    for ty in 0..tiles_y {
+        for tx in 0..tiles_x {
+            let (x0, x1) = ((tx as f32 * tile_width) as usize, ((tx + 1) as f32 * tile_width) as usize);
+            let (y0, y1) = ((ty as f32 * tile_height) as usize, ((ty + 1) as f32 * tile_height) as usize);
+            let mut histogram = histogram(
+                (y0..y1).flat_map(|y| (x0..x1).map(move |x| (x, y))).map(|(x, y)| values[y * width + x]),
+            );
+            clip_histogram(&mut histogram, clip_limit);
+            luts.push(equalization_lut(&histogram));
+        }
+    }
+
+    // Bilinear blend between neighbouring tile curves
+    let original = values.to_vec();
+    for y in 0..height {
+        let fy = ((y as f32 + 0.5) / tile_height - 0.5).clamp(0.0, (tiles_y - 1) as f32);
+        let (ty0, wy) = (fy.floor() as usize, fy.fract());
+        let ty1 = (ty0 + 1).min(tiles_y - 1);
+        for x in 0..width {
+            let fx = ((x as f32 + 0.5) / tile_width - 0.5).clamp(0.0, (tiles_x - 1) as f32);
+            let (tx0, wx) = (fx.floor() as usize, fx.fract());
+            let tx1 = (tx0 + 1).min(tiles_x - 1);
+
+            let v = original[y * width + x] as usize;
+            let at = |tx: usize, ty: usize| luts[ty * tiles_x + tx][v] as f32;
+            let top = at(tx0, ty0) * (1.0 - wx) + at(tx1, ty0) * wx;
+            let bottom = at(tx0, ty1) * (1.0 - wx) + at(tx1, ty1) * wx;
+            values[y * width + x] = (top * (1.0 - wy) + bottom * wy).round() as u8;
+        }
+    }
+}
+
+/// Clips histogram bins at `clip_limit` times the mean bin height and spreads the
+/// excess evenly across all bins, which bounds how much contrast CLAHE can add.
+fn clip_histogram(histogram: &mut [f32; LEVELS], clip_limit: f32) {
+    let total: f32 = histogram.iter().sum();
+    let limit = clip_limit * total / LEVELS as f32;
+
+    let mut excess = 0.0;
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+    let share = excess / LEVELS as f32;
+    for count in histogram.iter_mut() {
+        *count += share;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: ContrastSettings = ContrastSettings { percentile: 1.0, clip_limit: 2.0, tiles: 2 };
+
+    #[test]
+    fn test_equalize_spreads_narrow_range() {
+        let mut values: Vec<u8> = (0..64).map(|i| 100 + (i % 16) as u8).collect();
+        apply(&mut values, 8, 8, AutoContrast::Equalize, SETTINGS);
+        assert_eq!(*values.iter().min().unwrap(), 0);
+        assert_eq!(*values.iter().max().unwrap(), 255);
+    }
+
+    #[test]
+    fn test_auto_levels_stretches() {
+        let mut values: Vec<u8> = (0..100).map(|i| 50 + i as u8).collect();
+        apply(&mut values, 10, 10, AutoContrast::AutoLevels, SETTINGS);
+        assert!(values[0] == 0 && values[99] == 255);
+        // Order is preserved
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_flat_image_is_unchanged() {
+        for mode in [AutoContrast::Equalize, AutoContrast::AutoLevels] {
+            let mut values = vec![90u8; 36];
+            apply(&mut values, 6, 6, mode, SETTINGS);
+            assert!(values.iter().all(|&v| v == 90), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_clahe_boosts_local_contrast() {
+        // Dark left half and bright right half, each with faint detail
+        let mut values: Vec<u8> = (0..16 * 16)
+            .map(|i| {
+                let (x, y) = (i % 16, i / 16);
+                let base = if x < 8 { 30 } else { 200 };
+                base + ((x + y) % 2) as u8 * 6
+            })
+            .collect();
+        apply(&mut values, 16, 16, AutoContrast::Clahe, SETTINGS);
+        let spread = values[0].abs_diff(values[1]);
+        assert!(spread > 6, "local detail should be amplified, got {}", spread);
+    }
+}
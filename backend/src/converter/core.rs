@@ -1,67 +1,785 @@
-use image::{GenericImageView, Rgb};
-use crate::converter::{ascii_pixel::AsciiPixel, config::ConverterConfig, error::ConverterError};
+use std::borrow::Cow;
+use image::{imageops, DynamicImage, GrayImage, Luma, Rgb, Rgb32FImage, RgbImage};
+use crate::converter::{
+    animation::{self, AsciiAnimation, AsciiFrame},
+    ascii_pixel::AsciiPixel,
+    config::{AlphaMode, AutoContrast, BackgroundTheme, CellBackground, ColorMode, ConverterConfig, DensityCalibration, DitherMode, FilterStage, LuminanceModel, RenderMode, ToneCurve},
+    braille, budget, color, dither, edges,
+    density::{self, Calibration},
+    emoji::EmojiTable,
+    contrast::{self, ContrastSettings},
+    error::ConverterError,
+    filters,
+    fit::{self, FitSettings, Layout},
+    font::{GLYPH_HEIGHT, GLYPH_WIDTH},
+    glyph::Glyph,
+    grading::{self, GradingSettings, Matrix},
+    glyph_match::{GlyphMatcher, BLOCK_LEN},
+    mosaic,
+    palette::{self, Palette},
+    preprocess, resample,
+    threshold::{self, ThresholdSettings},
+    tone::{self, ToneSettings},
+};
+
+/// Brightest foreground (as 0-255 luminance) kept on a light background.
+const LIGHT_THEME_MAX_LUMINANCE: f32 = 128.0;
+
+/// Side of the thumbnail measured by `background_theme: auto`.
+const THEME_SAMPLE_SIZE: u32 = 64;
+
+/// Widths guessed from the last measured text length before the character budget search
+/// falls back to bisection.
+const BUDGET_ESTIMATES: usize = 4;
 
 /// Main converter struct (namespace only)
 pub struct Converter;
 
+/// The size-independent part of a conversion, done once per image however many output
+/// sizes are tried.
+struct Prepared {
+    /// The image with transparency flattened and the `before_resize` filters applied.
+    rgb: RgbImage,
+    /// Full-resolution opacity, for `alpha_mode: blank` and `keep`.
+    alpha: Option<GrayImage>,
+    /// Rasterized character set, for `render_mode: glyph_match`.
+    matcher: Option<GlyphMatcher>,
+    grading: Option<Matrix>,
+}
+
 impl Converter {
     /// Validates the configuration for sensible values.
     fn validate_config(config: &ConverterConfig) -> Result<(), ConverterError> {
+        if config.character_set.is_empty() {
+            return Err(ConverterError::InvalidParameter("Character set must not be empty".into()));
+        }
         if config.output_width == 0 {
             return Err(ConverterError::InvalidParameter("Output width must be greater than 0".into()));
         }
+        if config.max_chars == Some(0) {
+            return Err(ConverterError::InvalidParameter("Character budget must be greater than 0".into()));
+        }
+        if config.max_width == Some(0) || config.max_height == Some(0) {
+            return Err(ConverterError::InvalidParameter("Max width and height must be greater than 0".into()));
+        }
         if config.brightness_factor <= 0.0 {
             return Err(ConverterError::InvalidParameter("Brightness factor must be positive".into()));
         }
         if config.contrast_factor <= 0.0 {
             return Err(ConverterError::InvalidParameter("Contrast factor must be positive".into()));
         }
+        if config.cell_background != CellBackground::None && !config.is_color() {
+            return Err(ConverterError::InvalidParameter("Cell backgrounds require color output".into()));
+        }
+        if config.color_mode == ColorMode::Palette && config.palette.is_empty() {
+            return Err(ConverterError::InvalidParameter("Palette color mode requires a non-empty palette".into()));
+        }
+        if config.color_mode == ColorMode::GradientMap && config.gradient_map.len() < 2 {
+            return Err(ConverterError::InvalidParameter("Gradient map color mode needs at least two color stops".into()));
+        }
+        if !(config.saturation.is_finite() && config.saturation >= 0.0) {
+            return Err(ConverterError::InvalidParameter("Saturation must not be negative".into()));
+        }
+        if !config.hue_shift.is_finite() {
+            return Err(ConverterError::InvalidParameter("Hue shift must be a finite number".into()));
+        }
+        if !(-1.0..=1.0).contains(&config.temperature) {
+            return Err(ConverterError::InvalidParameter("Temperature must be in [-1, 1]".into()));
+        }
+        if !(0.0..=1.0).contains(&config.sepia) || !(0.0..=1.0).contains(&config.tint_strength) {
+            return Err(ConverterError::InvalidParameter("Sepia and tint strength must be in [0, 1]".into()));
+        }
+        if config.edge_threshold < 0.0 {
+            return Err(ConverterError::InvalidParameter("Edge threshold must not be negative".into()));
+        }
+        preprocess::validate(&config.preprocess)?;
+        filters::validate(&config.filters)?;
+        if !(0.0..50.0).contains(&config.auto_levels_percentile) {
+            return Err(ConverterError::InvalidParameter("Auto-levels percentile must be in [0, 50)".into()));
+        }
+        if config.clahe_clip_limit < 1.0 {
+            return Err(ConverterError::InvalidParameter("CLAHE clip limit must be at least 1".into()));
+        }
+        if config.clahe_tiles == 0 {
+            return Err(ConverterError::InvalidParameter("CLAHE tiles must be greater than 0".into()));
+        }
+        if !(config.gamma.is_finite() && config.gamma > 0.0) {
+            return Err(ConverterError::InvalidParameter("Gamma must be a positive number".into()));
+        }
+        if !(-1.0..=1.0).contains(&config.s_curve_strength) {
+            return Err(ConverterError::InvalidParameter("S-curve strength must be in [-1, 1]".into()));
+        }
+        if config.tone_curve == ToneCurve::Points
+            && (config.curve_points.len() < 2 || config.curve_points.windows(2).any(|pair| pair[0][0] >= pair[1][0]))
+        {
+            return Err(ConverterError::InvalidParameter(
+                "Curve points need at least two points with strictly increasing inputs".into(),
+            ));
+        }
+        if config.render_mode == RenderMode::Emoji && !config.emoji_builtin && config.emoji_table.is_empty() {
+            return Err(ConverterError::InvalidParameter("Emoji mode needs the built-in table or a custom emoji table".into()));
+        }
+        if config.render_mode == RenderMode::Threshold {
+            if config.character_set.len() < 2 {
+                return Err(ConverterError::InvalidParameter("Threshold mode needs at least two characters".into()));
+            }
+            if config.adaptive_window == 0 {
+                return Err(ConverterError::InvalidParameter("Adaptive window must be greater than 0".into()));
+            }
+        }
+        if !config.char_thresholds.is_empty() {
+            if config.char_thresholds.len() != config.character_set.len() {
+                return Err(ConverterError::InvalidParameter(format!(
+                    "Expected {} character thresholds (one per character), got {}",
+                    config.character_set.len(),
+                    config.char_thresholds.len()
+                )));
+            }
+            if config.char_thresholds.windows(2).any(|pair| pair[0] > pair[1]) {
+                return Err(ConverterError::InvalidParameter("Character thresholds must be in ascending order".into()));
+            }
+            if config.density_calibration != DensityCalibration::None {
+                return Err(ConverterError::InvalidParameter(
+                    "Character thresholds cannot be combined with density calibration".into(),
+                ));
+            }
+        }
         Ok(())
     }
 
-    /// Adjusts an RGB color for brightness and contrast.
+    /// The combined color grading matrix, or `None` if the grading leaves colors unchanged.
+    /// Built once per conversion and passed to `adjust_color`.
+    fn grading_matrix(config: &ConverterConfig) -> Option<Matrix> {
+        let grading = GradingSettings {
+            saturation: config.saturation,
+            hue_shift: config.hue_shift,
+            temperature: config.temperature,
+            sepia: config.sepia,
+            tint_color: config.tint_color,
+            tint_strength: config.tint_strength,
+        };
+        (!grading.is_identity()).then(|| grading.matrix())
+    }
+
+    /// Adjusts an RGB color for brightness and contrast, then applies the color `grading`.
     /// Brightness is applied first, then contrast.
-    fn adjust_color(rgb: &Rgb<u8>, brightness: f32, contrast: f32) -> [u8; 3] {
+    fn adjust_color(rgb: &Rgb<u8>, config: &ConverterConfig, grading: Option<&Matrix>) -> [u8; 3] {
+        let (brightness, contrast) = (config.brightness_factor, config.contrast_factor);
         let [r, g, b] = [rgb[0] as f32, rgb[1] as f32, rgb[2] as f32];
         // Apply brightness first, then contrast
         let adjust = |v: f32| (((v * brightness - 128.0) * contrast) + 128.0).clamp(0.0, 255.0);
-        [
+        let adjusted = [
             adjust(r) as u8,
             adjust(g) as u8,
             adjust(b) as u8,
-        ]
+        ];
+        match grading {
+            Some(matrix) => grading::apply(matrix, adjusted),
+            None => adjusted,
+        }
+    }
+
+    /// The intensity that picks a cell's glyph, from the configured `intensity_source`.
+    /// On a light background it is flipped, so dark pixels get dense glyphs.
+    fn intensity(rgb: [u8; 3], config: &ConverterConfig) -> u8 {
+        let intensity = color::intensity(rgb, config.intensity_source, config.luminance_model);
+        if config.background_theme == BackgroundTheme::Light { 255 - intensity } else { intensity }
+    }
+
+    /// Replaces `background_theme: auto` with `light` or `dark` depending on whether
+    /// the prepared image is bright on average.
+    fn resolve_theme<'a>(config: &'a ConverterConfig, img: &RgbImage) -> Cow<'a, ConverterConfig> {
+        if config.background_theme != BackgroundTheme::Auto {
+            return Cow::Borrowed(config);
+        }
+        let sample = imageops::thumbnail(img, img.width().min(THEME_SAMPLE_SIZE), img.height().min(THEME_SAMPLE_SIZE));
+        let total: u64 = sample.pixels().map(|pixel| color::luminance(pixel.0, config.luminance_model) as u64).sum();
+        let mean = total / (sample.width() as u64 * sample.height() as u64).max(1);
+        let background_theme = if mean >= 128 { BackgroundTheme::Light } else { BackgroundTheme::Dark };
+        Cow::Owned(ConverterConfig { background_theme, ..config.clone() })
+    }
+
+    /// Converts a prepared image. With `max_chars`, the output is the largest width up
+    /// to `widest` whose text fits the character budget, and the config sized for it is
+    /// returned with the grid. An explicit `output_height` shrinks in proportion.
+    ///
+    /// The text length depends on the glyphs and colors, so candidate widths are converted.
+    /// The first guess is the widest grid whose cells and newlines alone fit; later guesses
+    /// extrapolate from the last measured length, falling back to bisection.
+    fn convert_within_budget<'a>(
+        prepared: &Prepared,
+        config: &'a ConverterConfig,
+        calibration: Option<&Calibration>,
+        widest: u32,
+    ) -> Result<(Cow<'a, ConverterConfig>, Vec<Vec<AsciiPixel>>), ConverterError> {
+        let Some(budget) = config.max_chars else {
+            return Ok((Cow::Borrowed(config), Self::render(prepared, config, calibration)?));
+        };
+        let too_small = || ConverterError::InvalidParameter(format!("Character budget of {} is too small for this image", budget));
+        if widest == 0 {
+            return Err(too_small());
+        }
+        let (image_width, image_height) = prepared.rgb.dimensions();
+        let layout_for = |width: u32| {
+            let settings = FitSettings { output_width: width, output_height: Self::height_for(config, width), ..Self::fit_settings(config) };
+            fit::layout(image_width, image_height, settings)
+        };
+
+        // Widest grid whose cells and newlines fit, if every glyph is one code point
+        let cells = |width: u32| layout_for(width).map_or(0, |layout| (layout.width as usize + 1) * layout.height as usize - 1);
+        let (mut low, mut high) = (0, widest + 1);
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if cells(middle) <= budget { low = middle } else { high = middle }
+        }
+        let mut width = low.max(1);
+
+        // `low` fits the budget (`best` holds its conversion) and `high` does not
+        let (mut low, mut high) = (0, widest + 1);
+        let mut best = None;
+        let mut estimates = 0;
+        loop {
+            let mut length = None;
+            if layout_for(width).is_none() {
+                // No rows: narrower outputs have none either
+                high = width;
+            } else {
+                let sized = Self::with_width(config, width);
+                let grid = Self::render(prepared, &sized, calibration)?;
+                let measured = budget::text_length(&grid, sized.color_mode, sized.count_ansi);
+                if measured <= budget {
+                    low = width;
+                    best = Some((sized, grid));
+                } else {
+                    high = width;
+                }
+                length = Some(measured);
+            }
+            if high - low <= 1 {
+                break;
+            }
+            width = match length {
+                // The number of rows follows the width, so the length grows with its square
+                Some(length) if estimates < BUDGET_ESTIMATES => {
+                    estimates += 1;
+                    (width as f64 * (budget as f64 / length as f64).sqrt()) as u32
+                }
+                _ => low + (high - low) / 2,
+            }
+            .clamp(low + 1, high - 1);
+        }
+
+        let (sized, grid) = best.ok_or_else(too_small)?;
+        Ok((Cow::Owned(sized), grid))
+    }
+
+    /// Output height for `width` columns: an explicit `output_height` shrinks in proportion.
+    fn height_for(config: &ConverterConfig, width: u32) -> Option<u32> {
+        config
+            .output_height
+            .map(|height| ((height as u64 * width as u64) / config.output_width as u64).max(1) as u32)
+    }
+
+    /// The config resized to `width` columns.
+    fn with_width(config: &ConverterConfig, width: u32) -> ConverterConfig {
+        ConverterConfig { output_width: width, output_height: Self::height_for(config, width), ..config.clone() }
+    }
+
+    /// Darkens foreground colors that would be too light to read on a light background,
+    /// keeping their hue. Cells with their own background color are left alone.
+    fn darken_for_light_background(ascii_grid: &mut [Vec<AsciiPixel>]) {
+        for pixel in ascii_grid.iter_mut().flatten().filter(|pixel| pixel.bg.is_none()) {
+            pixel.rgb = pixel.rgb.map(|rgb| {
+                let luminance = color::luminance(rgb, LuminanceModel::Rec709) as f32;
+                if luminance <= LIGHT_THEME_MAX_LUMINANCE {
+                    return rgb;
+                }
+                let scale = LIGHT_THEME_MAX_LUMINANCE / luminance;
+                rgb.map(|v| (v as f32 * scale).round() as u8)
+            });
+        }
+    }
+
+    /// Runs the configured automatic contrast correction, then the tone curve, over a
+    /// row-major intensity grid.
+    fn adjust_tones(intensities: &mut [u8], width: u32, height: u32, config: &ConverterConfig) {
+        if config.auto_contrast != AutoContrast::None {
+            let settings = ContrastSettings {
+                percentile: config.auto_levels_percentile,
+                clip_limit: config.clahe_clip_limit,
+                tiles: config.clahe_tiles,
+            };
+            contrast::apply(intensities, width as usize, height as usize, config.auto_contrast, settings);
+        }
+        if config.tone_curve != ToneCurve::None {
+            let settings = ToneSettings {
+                gamma: config.gamma,
+                s_curve_strength: config.s_curve_strength,
+                points: &config.curve_points,
+            };
+            let lut = tone::lut(config.tone_curve, settings);
+            intensities.iter_mut().for_each(|v| *v = lut[*v as usize]);
+        }
+    }
+
+    /// Resizes the source image to `width` x `height` RGB pixels with the configured filter,
+    /// then runs the `after_resize` image filters.
+    /// With `linear_light`, pixels are decoded from sRGB first so the resampling averages
+    /// light rather than gamma-encoded values, then re-encoded.
+    fn resize_rgb(rgb: &RgbImage, width: u32, height: u32, config: &ConverterConfig) -> RgbImage {
+        let resized = if !config.linear_light {
+            resample::resize(rgb, width, height, config.resize_filter)
+        } else {
+            let linear = Rgb32FImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+                Rgb(rgb.get_pixel(x, y).0.map(color::decode))
+            });
+            let resized = resample::resize(&linear, width, height, config.resize_filter);
+            RgbImage::from_fn(width, height, |x, y| Rgb(resized.get_pixel(x, y).0.map(color::encode)))
+        };
+        filters::apply(resized, &config.filters, FilterStage::AfterResize)
+    }
+
+    /// Blends every pixel onto `background` by its alpha, removing transparency.
+    fn composite(img: &DynamicImage, background: [u8; 3]) -> RgbImage {
+        let rgba = img.to_rgba8();
+        RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+            let pixel = rgba.get_pixel(x, y);
+            let alpha = pixel[3] as f32 / 255.0;
+            Rgb(std::array::from_fn(|i| {
+                (pixel[i] as f32 * alpha + background[i] as f32 * (1.0 - alpha)).round() as u8
+            }))
+        })
     }
 
-    /// Maps a grayscale intensity (0-255) to a character from the set.
-    fn intensity_to_char(intensity: u8, chars: &[char]) -> char {
+    /// The alpha channel as a grayscale image.
+    fn alpha_channel(img: &DynamicImage) -> GrayImage {
+        let rgba = img.to_rgba8();
+        GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| Luma([rgba.get_pixel(x, y)[3]]))
+    }
+
+    /// Applies per-cell opacity: `blank` empties transparent cells, `keep` records the opacity.
+    /// A wide glyph and its continuation share the mean opacity of both columns, so a pair is
+    /// always blanked or kept as a whole.
+    fn apply_alpha(ascii_grid: &mut [Vec<AsciiPixel>], alpha: &GrayImage, config: &ConverterConfig) {
+        for (y, row) in ascii_grid.iter_mut().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let span = if row[x].ch.width() > 1 && x + 1 < row.len() { 2 } else { 1 };
+                let opacity = ((x..x + span).map(|x| alpha.get_pixel(x as u32, y as u32)[0] as u32).sum::<u32>()
+                    / span as u32) as u8;
+                for pixel in &mut row[x..x + span] {
+                    match config.alpha_mode {
+                        AlphaMode::Blank if opacity < config.alpha_threshold => {
+                            *pixel = AsciiPixel { ch: ' '.into(), rgb: None, bg: None, alpha: None };
+                        }
+                        AlphaMode::Keep => pixel.alpha = Some(opacity),
+                        _ => {}
+                    }
+                }
+                x += span;
+            }
+        }
+    }
+
+    /// Picks a foreground that stays legible on top of `bg`: bright backgrounds get a
+    /// darkened shade of the same color, dark backgrounds a lightened one.
+    fn contrasting(bg: [u8; 3]) -> [u8; 3] {
+        if color::luminance(bg, LuminanceModel::Rec709) >= 128 {
+            bg.map(|v| (v as f32 * 0.35) as u8)
+        } else {
+            bg.map(|v| (v as f32 + (255.0 - v as f32) * 0.65) as u8)
+        }
+    }
+
+    /// Moves each cell's color into the background and gives the glyph a contrasting
+    /// foreground. Cells that already carry a background (block mosaics) are left alone.
+    fn apply_cell_background(ascii_grid: &mut [Vec<AsciiPixel>]) {
+        for pixel in ascii_grid.iter_mut().flatten() {
+            if let (Some(rgb), None) = (pixel.rgb, pixel.bg) {
+                pixel.bg = Some(rgb);
+                pixel.rgb = Some(Self::contrasting(rgb));
+            }
+        }
+    }
+
+    /// Reduces cell colors to the configured color mode: palette modes map foreground and
+    /// background colors to their nearest palette entry, `grayscale_tint` replaces them
+    /// with shades of the tint color.
+    fn apply_color_mode(ascii_grid: &mut [Vec<AsciiPixel>], config: &ConverterConfig) {
+        let palette = match config.color_mode {
+            ColorMode::None | ColorMode::Truecolor => return,
+            ColorMode::GrayscaleTint => {
+                let tint = |rgb: [u8; 3]| {
+                    let intensity = color::luminance(rgb, config.luminance_model) as u32;
+                    config.tint_color.map(|v| (v as u32 * intensity / 255) as u8)
+                };
+                for pixel in ascii_grid.iter_mut().flatten() {
+                    pixel.rgb = pixel.rgb.map(tint);
+                    pixel.bg = pixel.bg.map(tint);
+                }
+                return;
+            }
+            ColorMode::GradientMap => {
+                let map = |rgb: [u8; 3]| grading::gradient(&config.gradient_map, color::luminance(rgb, config.luminance_model));
+                for pixel in ascii_grid.iter_mut().flatten() {
+                    pixel.rgb = pixel.rgb.map(map);
+                    pixel.bg = pixel.bg.map(map);
+                }
+                return;
+            }
+            ColorMode::Xterm256 => Palette::new(palette::xterm_256()),
+            ColorMode::Ansi16 => Palette::new(palette::ANSI_16.to_vec()),
+            ColorMode::Palette => Palette::new(config.palette.clone()),
+        };
+
+        let height = ascii_grid.len();
+        let width = ascii_grid.first().map_or(0, |row| row.len());
+        let foreground: Vec<_> = ascii_grid.iter().flatten().map(|pixel| pixel.rgb).collect();
+        let background: Vec<_> = ascii_grid.iter().flatten().map(|pixel| pixel.bg).collect();
+        let foreground = palette.quantize(&foreground, width, height, config.color_dither);
+        let background = palette.quantize(&background, width, height, config.color_dither);
+
+        for ((pixel, rgb), bg) in ascii_grid.iter_mut().flatten().zip(foreground).zip(background) {
+            pixel.rgb = rgb;
+            pixel.bg = bg;
+        }
+    }
+
+    /// Surrounds the rendered image with padding cells so it sits at its `layout` position.
+    /// A wide padding glyph is only placed where both of its columns fit; a space fills the gap.
+    fn pad(ascii_grid: Vec<Vec<AsciiPixel>>, layout: &Layout, padding: &Glyph) -> Vec<Vec<AsciiPixel>> {
+        if (layout.content_width, layout.content_height) == (layout.width, layout.height) {
+            return ascii_grid;
+        }
+        let wide = padding.width() == 2;
+        let padding_cells = |count: u32| {
+            (0..count).map(move |i| {
+                let ch = if !wide || (i % 2 == 0 && i + 1 < count) {
+                    padding.clone()
+                } else if i % 2 == 1 {
+                    Glyph::continuation()
+                } else {
+                    ' '.into()
+                };
+                AsciiPixel { ch, rgb: None, bg: None, alpha: None }
+            })
+        };
+
+        let right = layout.width - layout.left - layout.content_width;
+        let bottom = layout.height - layout.top - layout.content_height;
+        let blank_rows = |count: u32| (0..count).map(|_| padding_cells(layout.width).collect::<Vec<_>>()).collect::<Vec<_>>();
+        let mut padded = blank_rows(layout.top);
+        for row in ascii_grid {
+            padded.push(padding_cells(layout.left).chain(row).chain(padding_cells(right)).collect());
+        }
+        padded.extend(blank_rows(bottom));
+        padded
+    }
+
+    /// Gives every wide glyph a second column: the cell to its right becomes a continuation
+    /// with the same colors. A wide glyph in the last column has no room and becomes a space.
+    fn layout_wide_glyphs(ascii_grid: &mut [Vec<AsciiPixel>]) {
+        for row in ascii_grid {
+            let mut x = 0;
+            while x < row.len() {
+                if row[x].ch.width() > 1 {
+                    if x + 1 == row.len() {
+                        row[x].ch = ' '.into();
+                    } else {
+                        row[x + 1] = AsciiPixel { ch: Glyph::continuation(), ..row[x].clone() };
+                        x += 1;
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+
+    /// Maps a grayscale intensity (0-255) to a character from the set, using the explicit
+    /// per-character `thresholds` if there are any and evenly sized ranges otherwise.
+    fn intensity_to_char(intensity: u8, chars: &[Glyph], thresholds: &[u8]) -> Glyph {
+        if !thresholds.is_empty() {
+            return chars[tone::threshold_index(intensity, thresholds)].clone();
+        }
         let index = (intensity as usize * (chars.len() - 1)) / 255;
-        chars[index]
+        chars[index].clone()
     }
 
     /// Builds the ASCII grid from a generic image buffer using a pixel getter closure.
     /// The closure should return (intensity, Optional<rgb>) for each (x, y).
+    /// The whole grid is collected first so auto-contrast can look at every cell and,
+    /// when dithering is enabled, the quantization error can spread between cells.
     fn build_ascii_grid<F>(
         output_width: u32,
         output_height: u32,
-        character_set: &[char],
+        config: &ConverterConfig,
+        calibration: Option<&Calibration>,
         mut get_pixel: F,
     ) -> Vec<Vec<AsciiPixel>>
     where
         F: FnMut(u32, u32) -> (u8, Option<[u8; 3]>),
     {
+        let cell_count = (output_width * output_height) as usize;
+        let mut intensities = Vec::with_capacity(cell_count);
+        let mut colors = Vec::with_capacity(cell_count);
+        for y in 0..output_height {
+            for x in 0..output_width {
+                let (intensity, rgb) = get_pixel(x, y);
+                intensities.push(intensity);
+                colors.push(rgb);
+            }
+        }
+        Self::adjust_tones(&mut intensities, output_width, output_height, config);
+        Self::ramp_grid(&intensities, &colors, output_width, output_height, config, calibration)
+    }
+
+    /// Maps row-major intensities (after tone adjustment) onto the character ramp, pairing
+    /// every cell with its color. With a density `calibration`, intensities map onto the
+    /// measured glyph weights instead of evenly spaced levels.
+    fn ramp_grid(
+        intensities: &[u8],
+        colors: &[Option<[u8; 3]>],
+        output_width: u32,
+        output_height: u32,
+        config: &ConverterConfig,
+        calibration: Option<&Calibration>,
+    ) -> Vec<Vec<AsciiPixel>> {
+        let character_set = &config.character_set;
+        let chars: Vec<Glyph> = if let Some(calibration) = calibration {
+            let values: Vec<f32> = intensities.iter().map(|&intensity| intensity as f32).collect();
+            let glyphs = calibration.glyphs();
+            dither::quantize_to_levels(&values, output_width as usize, output_height as usize, &calibration.levels(), config.dither)
+                .into_iter()
+                .map(|index| glyphs[index].clone())
+                .collect()
+        } else if config.dither == DitherMode::None {
+            intensities
+                .iter()
+                .map(|&intensity| Self::intensity_to_char(intensity, character_set, &config.char_thresholds))
+                .collect()
+        } else {
+            let levels: Vec<f32> = intensities.iter().map(|&intensity| intensity as f32).collect();
+            let (width, height) = (output_width as usize, output_height as usize);
+            let indices = if config.char_thresholds.is_empty() {
+                dither::quantize(&levels, width, height, character_set.len(), config.dither)
+            } else {
+                // Dither towards the midpoint of each character's range
+                let targets = tone::threshold_levels(&config.char_thresholds);
+                dither::quantize_to_levels(&levels, width, height, &targets, config.dither)
+            };
+            indices.into_iter().map(|index| character_set[index].clone()).collect()
+        };
+
+        chars
+            .chunks(output_width as usize)
+            .zip(colors.chunks(output_width as usize))
+            .map(|(char_row, color_row)| {
+                char_row
+                    .iter()
+                    .zip(color_row)
+                    .map(|(ch, &rgb)| AsciiPixel { ch: ch.clone(), rgb, bg: None, alpha: None })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Edge-aware conversion: cells on strong gradients get a direction glyph,
+    /// everything else falls back to the regular intensity ramp. Both use the same
+    /// tone-adjusted intensities.
+    fn convert_edges(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>, calibration: Option<&Calibration>) -> Vec<Vec<AsciiPixel>> {
+        let img_rgb = Self::resize_rgb(img, output_width, output_height, config);
+        let adjusted: Vec<[u8; 3]> = img_rgb
+            .pixels()
+            .map(|pixel| Self::adjust_color(pixel, config, grading))
+            .collect();
+        let mut intensities: Vec<u8> = adjusted.iter().map(|&rgb| Self::intensity(rgb, config)).collect();
+        Self::adjust_tones(&mut intensities, output_width, output_height, config);
+        let luma: Vec<f32> = intensities.iter().map(|&v| v as f32).collect();
+
+        let edges = edges::detect(
+            &luma,
+            output_width as usize,
+            output_height as usize,
+            config.edge_detector,
+            config.edge_threshold,
+        );
+
+        let colors: Vec<Option<[u8; 3]>> = adjusted.iter().map(|&rgb| config.is_color().then_some(rgb)).collect();
+        let mut ascii_grid = Self::ramp_grid(&intensities, &colors, output_width, output_height, config, calibration);
+
+        // Overlay direction glyphs on top of the ramp output
+        for (pixel, edge) in ascii_grid.iter_mut().flatten().zip(edges) {
+            if let Some(ch) = edge {
+                pixel.ch = ch.into();
+            }
+        }
+        ascii_grid
+    }
+
+    /// Structure-aware conversion: the image is resized so every cell covers a
+    /// glyph-sized pixel block, and each block is matched against the rasterized character set.
+    fn convert_glyph_match(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>, matcher: &GlyphMatcher) -> Vec<Vec<AsciiPixel>> {
+        let block_width = GLYPH_WIDTH as u32;
+        let block_height = GLYPH_HEIGHT as u32;
+        let img_detail = Self::resize_rgb(img, output_width * block_width, output_height * block_height, config);
+        let mut detail: Vec<u8> = img_detail
+            .pixels()
+            .map(|pixel| {
+                let adjusted = Self::adjust_color(pixel, config, grading);
+                Self::intensity(adjusted, config)
+            })
+            .collect();
+        Self::adjust_tones(&mut detail, img_detail.width(), img_detail.height(), config);
+        // Cell colors come from a regular one-pixel-per-cell resize
+        let img_cells = config.is_color().then(|| Self::resize_rgb(img, output_width, output_height, config));
+
         let mut ascii_grid = Vec::with_capacity(output_height as usize);
+        let mut block = [0.0f32; BLOCK_LEN];
         for y in 0..output_height {
             let mut row = Vec::with_capacity(output_width as usize);
             for x in 0..output_width {
-                let (intensity, rgb) = get_pixel(x, y);
-                let ascii_char = Self::intensity_to_char(intensity, character_set);
-                row.push(AsciiPixel { ch: ascii_char, rgb });
+                for by in 0..block_height {
+                    for bx in 0..block_width {
+                        let index = (y * block_height + by) * img_detail.width() + x * block_width + bx;
+                        block[(by * block_width + bx) as usize] = detail[index as usize] as f32;
+                    }
+                }
+                let ch = config.character_set[matcher.best_match(&block)].clone();
+                let rgb = img_cells.as_ref().map(|cells| Self::adjust_color(cells.get_pixel(x, y), config, grading));
+                row.push(AsciiPixel { ch, rgb, bg: None, alpha: None });
             }
             ascii_grid.push(row);
         }
         ascii_grid
     }
-    
+
+    /// Block mosaic conversion: the image is resized to sub-cell resolution and each
+    /// cell encodes its sub-pixels with a block glyph plus foreground/background colors.
+    fn convert_mosaic(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>) -> Vec<Vec<AsciiPixel>> {
+        let layout = mosaic::sub_cells(config.render_mode).expect("mosaic render mode");
+        let img_sub = Self::resize_rgb(img, output_width * layout.columns, output_height * layout.rows, config);
+        let adjusted = |x: u32, y: u32| Self::adjust_color(img_sub.get_pixel(x, y), config, grading);
+
+        if config.is_color() {
+            mosaic::render_color(output_width, output_height, layout, adjusted)
+        } else {
+            let mut intensities: Vec<u8> = (0..img_sub.height())
+                .flat_map(|y| (0..img_sub.width()).map(move |x| (x, y)))
+                .map(|(x, y)| Self::intensity(adjusted(x, y), config))
+                .collect();
+            Self::adjust_tones(&mut intensities, img_sub.width(), img_sub.height(), config);
+            let luma: Vec<f32> = intensities.iter().map(|&v| v as f32).collect();
+            mosaic::render_gray(&luma, output_width, output_height, layout, config.dither)
+        }
+    }
+
+    /// Emoji mosaic: every emoji covers two columns, so the image is resized to half the
+    /// output width and each cell's color is matched to the nearest emoji in the table.
+    /// Both columns carry the emoji; the wide glyph layout turns the second into a continuation.
+    fn convert_emoji(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>) -> Vec<Vec<AsciiPixel>> {
+        let table = EmojiTable::new(&config.emoji_table, config.emoji_builtin);
+        let columns = output_width.div_ceil(2);
+        let img_cells = Self::resize_rgb(img, columns, output_height, config);
+        let colors: Vec<[u8; 3]> = img_cells
+            .pixels()
+            .map(|pixel| Self::adjust_color(pixel, config, grading))
+            .collect();
+        let glyphs = table.choose(&colors, columns as usize, output_height as usize, config.dither);
+
+        (0..output_height)
+            .map(|y| {
+                (0..output_width)
+                    .map(|x| {
+                        let index = (y * columns + x / 2) as usize;
+                        AsciiPixel {
+                            ch: glyphs[index].clone(),
+                            rgb: config.is_color().then_some(colors[index]),
+                            bg: None,
+                            alpha: None,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Braille conversion: the image is resized to 2x4 dots per cell, each dot is
+    /// thresholded, and color mode reports the cell's average color.
+    fn convert_braille(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>) -> Vec<Vec<AsciiPixel>> {
+        let img_dots = Self::resize_rgb(img, output_width * braille::DOT_COLUMNS, output_height * braille::DOT_ROWS, config);
+        let adjusted: Vec<[u8; 3]> = img_dots
+            .pixels()
+            .map(|pixel| Self::adjust_color(pixel, config, grading))
+            .collect();
+        let mut intensities: Vec<u8> = adjusted.iter().map(|&rgb| Self::intensity(rgb, config)).collect();
+        Self::adjust_tones(&mut intensities, img_dots.width(), img_dots.height(), config);
+        let luma: Vec<f32> = intensities.iter().map(|&v| v as f32).collect();
+
+        braille::render(
+            &luma,
+            config.is_color().then_some(adjusted.as_slice()),
+            output_width,
+            output_height,
+            config.braille_threshold,
+            config.dither,
+        )
+    }
+
+    /// Binary conversion: each cell's intensity is thresholded and drawn with the first
+    /// (dark) or last (bright) character of the set.
+    fn convert_threshold(img: &RgbImage, output_width: u32, output_height: u32, config: &ConverterConfig, grading: Option<&Matrix>) -> Vec<Vec<AsciiPixel>> {
+        let img_rgb = Self::resize_rgb(img, output_width, output_height, config);
+        let adjusted: Vec<[u8; 3]> = img_rgb
+            .pixels()
+            .map(|pixel| Self::adjust_color(pixel, config, grading))
+            .collect();
+        let mut intensities: Vec<u8> = adjusted.iter().map(|&rgb| Self::intensity(rgb, config)).collect();
+        Self::adjust_tones(&mut intensities, output_width, output_height, config);
+
+        let settings = ThresholdSettings {
+            threshold: config.binary_threshold,
+            window: config.adaptive_window,
+            offset: config.adaptive_offset,
+        };
+        let width = output_width as usize;
+        let bright = threshold::binarize(&intensities, width, output_height as usize, config.threshold_method, settings);
+        let (dark_char, bright_char) = (&config.character_set[0], &config.character_set[config.character_set.len() - 1]);
+
+        bright
+            .chunks(width)
+            .zip(adjusted.chunks(width))
+            .map(|(bright_row, color_row)| {
+                bright_row
+                    .iter()
+                    .zip(color_row)
+                    .map(|(&is_bright, &rgb)| AsciiPixel {
+                        ch: if is_bright { bright_char.clone() } else { dark_char.clone() },
+                        rgb: config.is_color().then_some(rgb),
+                        bg: None,
+                        alpha: None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Measures the glyph densities of the configured character set with the bundled
+    /// font, or with `calibration_font` if one was uploaded. `density_calibration: none`
+    /// measures without reordering, so the weights can still be reported.
+    pub fn calibrate(config: &ConverterConfig) -> Result<Calibration, ConverterError> {
+        if config.character_set.is_empty() {
+            return Err(ConverterError::InvalidParameter("Character set must not be empty".into()));
+        }
+        density::calibrate(&config.character_set, config.density_calibration, config.calibration_font.as_deref())
+    }
+
+    /// The calibration used for conversion, if `density_calibration` is enabled.
+    fn density_calibration(config: &ConverterConfig) -> Result<Option<Calibration>, ConverterError> {
+        if config.density_calibration == DensityCalibration::None {
+            return Ok(None);
+        }
+        Self::calibrate(config).map(Some)
+    }
 
     /// Converts an image (as bytes) to a 2D ASCII grid.
     /// Returns a grid of AsciiPixel structs.
@@ -73,53 +791,448 @@ impl Converter {
         
         // Load image from bytes
         let img = image::load_from_memory(image_bytes)?;
-        let (width, height) = img.dimensions();
+        let orientation = preprocess::exif_orientation(image_bytes);
+        let calibration = Self::density_calibration(&config)?;
+        // Orientation, crop, rotation and flips happen before any resizing
+        let img = preprocess::apply(img, orientation, &config.preprocess)?;
+        let prepared = Self::prepare(&img, &config)?;
+        let config = Self::resolve_theme(&config, &prepared.rgb);
+        let (_, grid) = Self::convert_within_budget(&prepared, &config, calibration.as_ref(), config.output_width)?;
+        Ok(grid)
+    }
 
-        // Calculate output height if not specified
-        let output_height = config.output_height.unwrap_or_else(|| {
-            ((config.output_width as f32 * height as f32 / width as f32) * config.aspect_ratio_correction) as u32
+    /// Converts every frame of an animated GIF, APNG or WebP with the same config.
+    /// Still images produce a single frame.
+    pub fn convert_animation_from_bytes(
+        image_bytes: &[u8],
+        config: ConverterConfig
+    ) -> Result<AsciiAnimation, ConverterError> {
+        Self::validate_config(&config)?;
+        if config.max_frames == 0 {
+            return Err(ConverterError::InvalidParameter("Max frames must be greater than 0".into()));
+        }
+
+        // Frames are converted as they are decoded, so only one full-size canvas is in memory
+        let max_frames = config.max_frames as usize;
+        let mut decoded = animation::decode_frames(image_bytes, max_frames)?;
+        let first = decoded
+            .next()
+            .ok_or_else(|| ConverterError::InvalidParameter("Animation contains no frames".into()))??;
+        let orientation = preprocess::exif_orientation(image_bytes);
+        let calibration = Self::density_calibration(&config)?;
+        let prepare = |image: DynamicImage, config: &ConverterConfig| {
+            Self::prepare(&preprocess::apply(image, orientation, &config.preprocess)?, config)
+        };
+        let first_prepared = prepare(first.image, &config)?;
+        // Decided once from the first frame, so the theme cannot flicker
+        let config = Self::resolve_theme(&config, &first_prepared.rgb);
+
+        // Every frame gets the widest output that fits the budget, up to the narrowest so far
+        let mut narrowest = config.output_width;
+        let mut frames = Vec::new();
+        let rest = decoded.map(|frame| frame.and_then(|frame| Ok((frame.delay_ms, prepare(frame.image, &config)?))));
+        for frame in std::iter::once(Ok((first.delay_ms, first_prepared))).chain(rest) {
+            let (delay_ms, prepared) = frame?;
+            let (sized, grid) = Self::convert_within_budget(&prepared, &config, calibration.as_ref(), narrowest)?;
+            narrowest = sized.output_width;
+            frames.push((narrowest, AsciiFrame { delay_ms, grid }));
+        }
+
+        // Frames converted before the narrowest one are converted again at its width. The
+        // width only shrinks, so they are the leading frames and decoding stops after them.
+        let sized = Self::with_width(&config, narrowest);
+        let wider = frames.iter_mut().take_while(|(width, _)| *width > narrowest);
+        for (frame, (_, converted)) in animation::decode_frames(image_bytes, max_frames)?.zip(wider) {
+            converted.grid = Self::render(&prepare(frame?.image, &sized)?, &sized, calibration.as_ref())?;
+        }
+
+        let frames: Vec<_> = frames.into_iter().map(|(_, frame)| frame).collect();
+        let height = frames[0].grid.len() as u32;
+        let width = frames[0].grid.first().map_or(0, |row| row.len() as u32);
+        Ok(AsciiAnimation { width, height, frames })
+    }
+
+    /// Sizing options of the config.
+    fn fit_settings(config: &ConverterConfig) -> FitSettings {
+        FitSettings {
+            output_width: config.output_width,
+            output_height: config.output_height,
+            max_width: config.max_width,
+            max_height: config.max_height,
+            aspect_ratio_correction: config.aspect_ratio_correction,
+            fit: config.fit,
+            gravity: config.gravity,
+            no_upscale: config.no_upscale,
+        }
+    }
+
+    /// Does the work that does not depend on the output size: alpha handling, the
+    /// `before_resize` filters, the color grading matrix and the glyph rasterization.
+    fn prepare(img: &DynamicImage, config: &ConverterConfig) -> Result<Prepared, ConverterError> {
+        let has_alpha = img.color().has_alpha() && config.alpha_mode != AlphaMode::Ignore;
+
+        // Opacity is kept from the original image, then transparency is flattened away
+        let alpha = (has_alpha && matches!(config.alpha_mode, AlphaMode::Blank | AlphaMode::Keep))
+            .then(|| Self::alpha_channel(img));
+        let rgb = if has_alpha { Self::composite(img, config.alpha_background) } else { img.to_rgb8() };
+        let rgb = filters::apply(rgb, &config.filters, FilterStage::BeforeResize);
+        let matcher = match config.render_mode {
+            RenderMode::GlyphMatch => Some(GlyphMatcher::new(&config.character_set, config.glyph_metric)?),
+            _ => None,
+        };
+        Ok(Prepared { rgb, alpha, matcher, grading: Self::grading_matrix(config) })
+    }
+
+    /// Converts a prepared image: fitting, the selected render mode and the final color
+    /// and alpha steps.
+    fn render(
+        prepared: &Prepared,
+        config: &ConverterConfig,
+        calibration: Option<&Calibration>,
+    ) -> Result<Vec<Vec<AsciiPixel>>, ConverterError> {
+        let (width, height) = prepared.rgb.dimensions();
+
+        // Output size, and the crop (cover) or padding (contain) that fits the image into it
+        let layout = fit::layout(width, height, Self::fit_settings(config))
+            .ok_or_else(|| ConverterError::InvalidParameter("Calculated output height is 0".into()))?;
+        let img = match layout.crop {
+            Some(crop) => Cow::Owned(imageops::crop_imm(&prepared.rgb, crop.x, crop.y, crop.width, crop.height).to_image()),
+            None => Cow::Borrowed(&prepared.rgb),
+        };
+        let (output_width, output_height) = (layout.content_width, layout.content_height);
+        // One opacity value per output cell
+        let cell_alpha = prepared.alpha.as_ref().map(|alpha| {
+            let alpha = match layout.crop {
+                Some(crop) => Cow::Owned(imageops::crop_imm(alpha, crop.x, crop.y, crop.width, crop.height).to_image()),
+                None => Cow::Borrowed(alpha),
+            };
+            resample::resize(&*alpha, output_width, output_height, config.resize_filter)
         });
 
-        if output_height == 0 {
-            return Err(ConverterError::InvalidParameter("Calculated output height is 0".into()));
-        }
-
-        // Branch for color or grayscale processing, but use the same grid builder
-        if config.is_color {
-            let img_rgb = image::imageops::resize(
-                &img.to_rgb8(), 
-                config.output_width, 
-                output_height, 
-                image::imageops::FilterType::Lanczos3
-            );
-            Ok(Self::build_ascii_grid(
-                config.output_width,
-                output_height,
-                &config.character_set,
-                |x, y| {
-                    let pixel = img_rgb.get_pixel(x, y);
-                    let adjusted_rgb = Self::adjust_color(pixel, config.brightness_factor, config.contrast_factor);
-                    let intensity = (0.299 * adjusted_rgb[0] as f32 + 0.587 * adjusted_rgb[1] as f32 + 0.114 * adjusted_rgb[2] as f32) as u8;
-                    (intensity, Some(adjusted_rgb))
-                },
-            ))
-        } else {
-            let img_gray = image::imageops::resize(
-                &img.to_luma8(),
-                config.output_width,
-                output_height,
-                image::imageops::FilterType::Nearest,
-            );
-            Ok(Self::build_ascii_grid(
-                config.output_width,
-                output_height,
-                &config.character_set,
-                |x, y| {
-                    let pixel = img_gray.get_pixel(x, y);
-                    let intensity = pixel[0]; // Luma pixel intensity
-                    (intensity, None) // No color for no-color output
-                },
-            ))
-        }
-    }
-}
\ No newline at end of file
+        let grading = prepared.grading.as_ref();
+        // Branch on render mode, then color or grayscale processing, sharing the same grid builder
+        let mut ascii_grid = match config.render_mode {
+            RenderMode::Edges => Self::convert_edges(&img, output_width, output_height, config, grading, calibration),
+            RenderMode::GlyphMatch => {
+                let matcher = prepared.matcher.as_ref().expect("glyph matcher is prepared for glyph_match");
+                Self::convert_glyph_match(&img, output_width, output_height, config, grading, matcher)
+            }
+            RenderMode::HalfBlock | RenderMode::Quadrant | RenderMode::Sextant => {
+                Self::convert_mosaic(&img, output_width, output_height, config, grading)
+            }
+            RenderMode::Braille => Self::convert_braille(&img, output_width, output_height, config, grading),
+            RenderMode::Emoji => Self::convert_emoji(&img, output_width, output_height, config, grading),
+            RenderMode::Threshold => Self::convert_threshold(&img, output_width, output_height, config, grading),
+            RenderMode::Ramp => {
+                let img_rgb = Self::resize_rgb(&img, output_width, output_height, config);
+                Self::build_ascii_grid(
+                    output_width,
+                    output_height,
+                    config,
+                    calibration,
+                    |x, y| {
+                        let pixel = img_rgb.get_pixel(x, y);
+                        let adjusted_rgb = Self::adjust_color(pixel, config, grading);
+                        let intensity = Self::intensity(adjusted_rgb, config);
+                        (intensity, config.is_color().then_some(adjusted_rgb)) // No color for no-color output
+                    },
+                )
+            }
+        };
+        if config.cell_background == CellBackground::Average {
+            Self::apply_cell_background(&mut ascii_grid);
+        }
+        // Tint and gradient map colors are chosen by the user, so only image colors are darkened
+        if config.background_theme == BackgroundTheme::Light
+            && !matches!(config.color_mode, ColorMode::GrayscaleTint | ColorMode::GradientMap)
+        {
+            Self::darken_for_light_background(&mut ascii_grid);
+        }
+        Self::apply_color_mode(&mut ascii_grid, config);
+        // Wide glyphs are laid out first so transparency treats each pair as one cell
+        Self::layout_wide_glyphs(&mut ascii_grid);
+        if let Some(alpha) = cell_alpha {
+            Self::apply_alpha(&mut ascii_grid, &alpha, config);
+        }
+        Ok(Self::pad(ascii_grid, &layout, &config.padding_char))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use serde_json::json;
+
+    fn png(img: &RgbaImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    fn config(json: serde_json::Value) -> ConverterConfig {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_cell_background_takes_the_average_color() {
+        let red = png(&RgbaImage::from_pixel(8, 8, Rgba([200, 30, 30, 255])));
+        let grid = Converter::convert_from_bytes(
+            &red,
+            config(json!({ "output_width": 4, "output_height": 2, "color_mode": "truecolor", "cell_background": "average" })),
+        )
+        .unwrap();
+        for pixel in grid.iter().flatten() {
+            assert_eq!(pixel.bg, Some([200, 30, 30]));
+            // A dark background gets a lighter shade of itself
+            assert_eq!(pixel.rgb, Some(Converter::contrasting([200, 30, 30])));
+            assert!(color::luminance(pixel.rgb.unwrap(), LuminanceModel::Rec709) >= 128);
+        }
+
+        // Both colors then snap to the palette: the background stays red, the foreground turns white
+        let grid = Converter::convert_from_bytes(
+            &red,
+            config(json!({
+                "output_width": 4,
+                "output_height": 2,
+                "color_mode": "palette",
+                "palette": [[0, 0, 0], [255, 255, 255], [200, 30, 30]],
+                "cell_background": "average"
+            })),
+        )
+        .unwrap();
+        for pixel in grid.iter().flatten() {
+            assert_eq!((pixel.rgb, pixel.bg), (Some([255, 255, 255]), Some([200, 30, 30])));
+        }
+    }
+
+    /// An 8x8 red image whose first column is fully transparent.
+    fn transparent_edge() -> Vec<u8> {
+        png(&RgbaImage::from_fn(8, 8, |x, _| Rgba([255, 0, 0, if x == 0 { 0 } else { 255 }])))
+    }
+
+    #[test]
+    fn test_blank_alpha_empties_whole_emoji_pairs() {
+        let grid = Converter::convert_from_bytes(
+            &transparent_edge(),
+            config(json!({ "output_width": 8, "output_height": 2, "render_mode": "emoji", "alpha_mode": "blank" })),
+        )
+        .unwrap();
+        for row in &grid {
+            let row: Vec<_> = row.iter().map(|pixel| pixel.ch.as_str()).collect();
+            assert_eq!(row, [" ", " ", "🟥", "", "🟥", "", "🟥", ""]);
+        }
+
+        let grid = Converter::convert_from_bytes(
+            &transparent_edge(),
+            config(json!({ "output_width": 8, "output_height": 2, "alpha_mode": "blank" })),
+        )
+        .unwrap();
+        for row in &grid {
+            assert_eq!(row[0].ch.as_str(), " ");
+            assert!(row[1..].iter().all(|pixel| pixel.ch.as_str() != " "));
+        }
+    }
+
+    #[test]
+    fn test_kept_alpha_is_shared_by_emoji_pairs() {
+        let grid = Converter::convert_from_bytes(
+            &transparent_edge(),
+            config(json!({ "output_width": 8, "output_height": 2, "render_mode": "emoji", "alpha_mode": "keep" })),
+        )
+        .unwrap();
+        for row in &grid {
+            for pair in row.chunks(2) {
+                assert_eq!(pair[1].ch.as_str(), "");
+                assert_eq!(pair[0].alpha, pair[1].alpha);
+            }
+            assert!(row[0].alpha.unwrap() < 255);
+            assert_eq!(row[7].alpha, Some(255));
+        }
+    }
+
+    fn cells(glyphs: &[&str]) -> Vec<AsciiPixel> {
+        glyphs
+            .iter()
+            .map(|&glyph| AsciiPixel { ch: serde_json::from_value(json!(glyph)).unwrap(), rgb: Some([1, 2, 3]), bg: None, alpha: None })
+            .collect()
+    }
+
+    fn glyphs(row: &[AsciiPixel]) -> Vec<&str> {
+        row.iter().map(|pixel| pixel.ch.as_str()).collect()
+    }
+
+    #[test]
+    fn test_wide_glyph_gets_a_continuation_cell() {
+        let mut grid = vec![cells(&["🟥", "🟥", "a", "🟦", "b"])];
+        Converter::layout_wide_glyphs(&mut grid);
+        assert_eq!(glyphs(&grid[0]), ["🟥", "", "a", "🟦", ""]);
+        // The continuation keeps the colors of its glyph
+        assert_eq!(grid[0][1].rgb, Some([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_wide_glyph_in_the_last_column_becomes_a_space() {
+        let mut grid = vec![cells(&["a", "b", "🟥"]), cells(&["🟥", "🟥", "🟥"])];
+        Converter::layout_wide_glyphs(&mut grid);
+        assert_eq!(glyphs(&grid[0]), ["a", "b", " "]);
+        assert_eq!(glyphs(&grid[1]), ["🟥", "", " "]);
+    }
+
+    /// Columns and rows of the grid that hold image cells rather than padding.
+    fn content_cells(grid: &[Vec<AsciiPixel>]) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for (y, row) in grid.iter().enumerate() {
+            cells.extend(row.iter().enumerate().filter(|(_, pixel)| pixel.rgb.is_some()).map(|(x, _)| (x, y)));
+        }
+        cells
+    }
+
+    #[test]
+    fn test_contain_pads_to_the_full_grid() {
+        let wide = png(&RgbaImage::from_pixel(20, 10, Rgba([200, 30, 30, 255])));
+        let settings = |gravity: &str| {
+            config(json!({
+                "output_width": 10,
+                "output_height": 10,
+                "aspect_ratio_correction": 1.0,
+                "color_mode": "truecolor",
+                "fit": "contain",
+                "gravity": gravity
+            }))
+        };
+        let content_at = |top: usize| (top..top + 5).flat_map(|y| (0..10).map(move |x| (x, y))).collect::<Vec<_>>();
+
+        for (gravity, top) in [("top", 0), ("center", 2), ("bottom", 5)] {
+            let grid = Converter::convert_from_bytes(&wide, settings(gravity)).unwrap();
+            assert_eq!(grid.len(), 10);
+            assert!(grid.iter().all(|row| row.len() == 10));
+            assert_eq!(content_cells(&grid), content_at(top), "{}", gravity);
+            // Padding cells are plain padding glyphs without colors
+            assert!(grid.iter().flatten().filter(|pixel| pixel.rgb.is_none()).all(|pixel| pixel.ch == ' '));
+        }
+    }
+
+    #[test]
+    fn test_wide_padding_keeps_rows_aligned() {
+        let tall = png(&RgbaImage::from_pixel(10, 20, Rgba([200, 30, 30, 255])));
+        let grid = Converter::convert_from_bytes(
+            &tall,
+            config(json!({
+                "output_width": 9,
+                "output_height": 10,
+                "aspect_ratio_correction": 1.0,
+                "color_mode": "truecolor",
+                "fit": "contain",
+                "padding_char": "🟦"
+            })),
+        )
+        .unwrap();
+
+        // Content is 5 columns wide, centered with two padding columns on the left and right
+        let content: Vec<_> = (0..10).flat_map(|y| (2..7).map(move |x| (x, y))).collect();
+        assert_eq!(content_cells(&grid), content);
+        for row in &grid {
+            assert_eq!(row.len(), 9);
+            assert_eq!(row.iter().map(|pixel| pixel.ch.width()).sum::<usize>(), 9);
+            assert_eq!(glyphs(&row[..2]), ["🟦", ""]);
+            assert_eq!(glyphs(&row[7..]), ["🟦", ""]);
+        }
+    }
+
+    /// A colorful 64x32 test card: every pixel differs from its neighbors.
+    fn test_card(seed: u32) -> RgbaImage {
+        RgbaImage::from_fn(64, 32, |x, y| {
+            let v = (x * 37 + y * 91 + seed * 53) % 256;
+            Rgba([v as u8, (v * 7 % 256) as u8, (255 - v) as u8, 255])
+        })
+    }
+
+    fn text_length(grid: &[Vec<AsciiPixel>], config: &ConverterConfig) -> usize {
+        budget::text_length(grid, config.color_mode, config.count_ansi)
+    }
+
+    #[test]
+    fn test_budget_picks_the_widest_output_that_fits() {
+        let card = png(&test_card(0));
+        for (budget, count_ansi) in [(2000, true), (2000, false), (50, false)] {
+            let settings = config(json!({ "output_width": 120, "color_mode": "truecolor", "count_ansi": count_ansi }));
+            let grid = Converter::convert_from_bytes(&card, ConverterConfig { max_chars: Some(budget), ..settings.clone() }).unwrap();
+            assert!(text_length(&grid, &settings) <= budget);
+
+            // One column wider no longer fits
+            let width = grid[0].len() as u32;
+            assert!(width < 120);
+            let wider = Converter::convert_from_bytes(&card, ConverterConfig { output_width: width + 1, ..settings.clone() }).unwrap();
+            assert!(text_length(&wider, &settings) > budget, "{} at width {}", budget, width + 1);
+        }
+    }
+
+    #[test]
+    fn test_budget_shrinks_an_explicit_height_in_proportion() {
+        let card = png(&test_card(0));
+        let grid = Converter::convert_from_bytes(
+            &card,
+            config(json!({ "output_width": 100, "output_height": 40, "max_chars": 500 })),
+        )
+        .unwrap();
+        let width = grid[0].len();
+        assert!(width < 100);
+        assert_eq!(grid.len(), (40 * width / 100).max(1));
+        assert!(text_length(&grid, &ConverterConfig::default()) <= 500);
+    }
+
+    #[test]
+    fn test_budget_fits_every_animation_frame() {
+        use image::{codecs::gif::GifEncoder, Delay, Frame};
+
+        // A flat first frame fits much wider than the busy frames after it
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            let frames = [RgbaImage::from_pixel(64, 32, Rgba([0, 0, 0, 255])), test_card(0), test_card(1)];
+            for buffer in frames {
+                encoder.encode_frame(Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(50, 1))).unwrap();
+            }
+        }
+        let settings = config(json!({ "output_width": 120, "color_mode": "truecolor", "count_ansi": true, "max_chars": 2000 }));
+        let animation = Converter::convert_animation_from_bytes(&gif, settings.clone()).unwrap();
+
+        assert_eq!(animation.frames.len(), 3);
+        for frame in &animation.frames {
+            assert_eq!(frame.grid.len(), animation.height as usize);
+            assert!(frame.grid.iter().all(|row| row.len() == animation.width as usize));
+            assert!(text_length(&frame.grid, &settings) <= 2000);
+        }
+        // The flat frame alone would have fit a wider output
+        let flat = &animation.frames[0].grid;
+        let alone = Converter::convert_animation_from_bytes(&gif, ConverterConfig { max_frames: 1, ..settings }).unwrap();
+        assert!(alone.width > animation.width);
+        assert!(flat.iter().flatten().all(|pixel| pixel.rgb == Some([0, 0, 0])));
+    }
+
+    #[test]
+    fn test_auto_theme_sees_the_composited_image() {
+        // Fully transparent: composited onto white, the image is bright
+        let clear = png(&RgbaImage::new(50, 50));
+        let grid = Converter::convert_from_bytes(
+            &clear,
+            config(json!({
+                "output_width": 10,
+                "background_theme": "auto",
+                "alpha_mode": "composite",
+                "alpha_background": [255, 255, 255]
+            })),
+        )
+        .unwrap();
+        assert!(grid.iter().flatten().all(|pixel| pixel.ch == ' '), "{:?}", grid[0]);
+    }
+
+    #[test]
+    fn test_contrasting_foreground() {
+        let light = Converter::contrasting([240, 240, 200]);
+        assert!(light.iter().zip([240, 240, 200]).all(|(&fg, bg)| fg < bg), "{:?}", light);
+        let dark = Converter::contrasting([20, 40, 80]);
+        assert!(dark.iter().zip([20, 40, 80]).all(|(&fg, bg)| fg > bg), "{:?}", dark);
+    }
+}
@@ -0,0 +1,152 @@
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use serde::Serialize;
+use crate::converter::{config::DensityCalibration, error::ConverterError, font, glyph::Glyph};
+
+/// Pixel height glyphs are rasterized at when measuring an uploaded font.
+const FONT_SCALE: f32 = 48.0;
+
+/// Glyphs whose normalized weights differ by less than this are treated as duplicates by `sort`.
+const DUPLICATE_TOLERANCE: f32 = 0.005;
+
+/// Measured density of one glyph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GlyphWeight {
+    pub ch: Glyph,
+    /// Fraction of the character cell covered by ink (0.0-1.0).
+    pub coverage: f32,
+    /// Coverage rescaled so the lightest glyph of the set is 0.0 and the densest is 1.0.
+    pub weight: f32,
+}
+
+/// A character set calibrated against the measured density of its glyphs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Calibration {
+    /// The calibrated character set, in the order used for conversion.
+    pub characters: String,
+    /// Per-glyph weights, in the same order as `characters`.
+    pub glyphs: Vec<GlyphWeight>,
+}
+
+impl Calibration {
+    /// The calibrated characters as a `character_set`.
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        self.glyphs.iter().map(|glyph| glyph.ch.clone()).collect()
+    }
+
+    /// Target intensity (0-255) of each glyph, for `dither::quantize_to_levels`.
+    pub fn levels(&self) -> Vec<f32> {
+        self.glyphs.iter().map(|glyph| glyph.weight * 255.0).collect()
+    }
+}
+
+/// Measures every character of `character_set` with the bundled bitmap font, or with
+/// `font_data` (a TTF/OTF file) if given. With `DensityCalibration::Sort` the glyphs are
+/// reordered from lightest to densest and near-identical ones are dropped; otherwise the
+/// order is kept.
+pub fn calibrate(character_set: &[Glyph], mode: DensityCalibration, font_data: Option<&[u8]>) -> Result<Calibration, ConverterError> {
+    let coverage = match font_data {
+        Some(data) => measure_font(character_set, data)?,
+        None => measure_bundled(character_set)?,
+    };
+
+    let (min, max) = coverage.iter().fold((f32::MAX, f32::MIN), |(lo, hi), &c| (lo.min(c), hi.max(c)));
+    let range = max - min;
+    let mut glyphs: Vec<GlyphWeight> = character_set
+        .iter()
+        .zip(coverage)
+        .map(|(ch, coverage)| GlyphWeight {
+            ch: ch.clone(),
+            coverage,
+            weight: if range > 0.0 { (coverage - min) / range } else { 0.0 },
+        })
+        .collect();
+
+    if mode == DensityCalibration::Sort {
+        glyphs.sort_by(|a, b| a.weight.total_cmp(&b.weight));
+        glyphs.dedup_by(|next, kept| next.weight - kept.weight < DUPLICATE_TOLERANCE);
+    }
+
+    Ok(Calibration {
+        characters: glyphs.iter().map(|glyph| glyph.ch.as_str()).collect(),
+        glyphs,
+    })
+}
+
+/// Ink coverage of each character in the bundled 6x10 bitmap font.
+fn measure_bundled(character_set: &[Glyph]) -> Result<Vec<f32>, ConverterError> {
+    character_set
+        .iter()
+        .map(|glyph| {
+            glyph.as_char().and_then(font::glyph_bitmap)
+                .map(|bitmap| bitmap.iter().sum::<f32>() / bitmap.len() as f32)
+                .ok_or_else(|| ConverterError::InvalidParameter(
+                    format!("Density calibration has no bitmap for character {:?}; upload a font that covers it", glyph)
+                ))
+        })
+        .collect()
+}
+
+/// Ink coverage of each character rasterized from a TTF/OTF font. Every glyph is measured
+/// against the same cell: the widest advance in the set by the font's line height.
+/// Glyphs must be single code points, since clusters would need text shaping.
+fn measure_font(character_set: &[Glyph], font_data: &[u8]) -> Result<Vec<f32>, ConverterError> {
+    let font = FontRef::try_from_slice(font_data)
+        .map_err(|e| ConverterError::InvalidParameter(format!("Could not read font: {}", e)))?;
+    let scaled = font.as_scaled(PxScale::from(FONT_SCALE));
+
+    let mut glyph_ids = Vec::with_capacity(character_set.len());
+    for glyph in character_set {
+        let ch = glyph.as_char().ok_or_else(|| ConverterError::InvalidParameter(
+            format!("Density calibration cannot measure the multi-code-point glyph {:?}", glyph)
+        ))?;
+        let id = font.glyph_id(ch);
+        if id.0 == 0 && !ch.is_whitespace() {
+            return Err(ConverterError::InvalidParameter(format!("Font has no glyph for character {:?}", ch)));
+        }
+        glyph_ids.push(id);
+    }
+
+    let cell_width = glyph_ids.iter().map(|&id| scaled.h_advance(id)).fold(1.0, f32::max);
+    let cell_area = cell_width * scaled.height().max(1.0);
+    Ok(glyph_ids
+        .into_iter()
+        .map(|id| {
+            let glyph = id.with_scale_and_position(FONT_SCALE, point(0.0, scaled.ascent()));
+            let mut ink = 0.0;
+            if let Some(outline) = font.outline_glyph(glyph) {
+                outline.draw(|_, _, coverage| ink += coverage);
+            }
+            ink / cell_area
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remap_keeps_order() {
+        let calibration = calibrate(&Glyph::split(" .:-@"), DensityCalibration::Remap, None).unwrap();
+        assert_eq!(calibration.characters, " .:-@");
+        assert_eq!(calibration.glyphs[0].weight, 0.0);
+        assert_eq!(calibration.glyphs[4].weight, 1.0);
+        // '-' (5 pixels) has half the ink of ':' (10 pixels) in the bundled font
+        assert!(calibration.glyphs[3].weight < calibration.glyphs[2].weight);
+    }
+
+    #[test]
+    fn test_sort_reorders_and_deduplicates() {
+        // '#' (20 pixels) is denser than '@' (18); '-' and '.' both have 5 pixels
+        let calibration = calibrate(&Glyph::split("@ #.-#"), DensityCalibration::Sort, None).unwrap();
+        assert_eq!(calibration.characters, " .@#");
+        assert!(calibration.glyphs.windows(2).all(|pair| pair[0].weight < pair[1].weight));
+        assert_eq!(calibration.levels().last(), Some(&255.0));
+    }
+
+    #[test]
+    fn test_missing_glyphs_are_rejected() {
+        assert!(calibrate(&Glyph::split("a\u{263A}"), DensityCalibration::Remap, None).is_err());
+        assert!(calibrate(&Glyph::split("a"), DensityCalibration::Remap, Some(b"not a font")).is_err());
+    }
+}
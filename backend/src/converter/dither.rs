@@ -0,0 +1,215 @@
+use crate::converter::config::DitherMode;
+
+/// 4x4 Bayer threshold matrix used for ordered dithering.
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [ 0,  8,  2, 10],
+    [12,  4, 14,  6],
+    [ 3, 11,  1,  9],
+    [15,  7, 13,  5],
+];
+
+/// Floyd–Steinberg error distribution as (dx, dy, weight / 16).
+const FLOYD_STEINBERG: [(i32, i32, f32); 4] = [
+    ( 1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    ( 0, 1, 5.0 / 16.0),
+    ( 1, 1, 1.0 / 16.0),
+];
+
+/// Atkinson error distribution as (dx, dy, weight / 8).
+/// Only 6/8 of the error is propagated, which keeps highlights and shadows clean.
+const ATKINSON: [(i32, i32, f32); 6] = [
+    ( 1, 0, 1.0 / 8.0),
+    ( 2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    ( 0, 1, 1.0 / 8.0),
+    ( 1, 1, 1.0 / 8.0),
+    ( 0, 2, 1.0 / 8.0),
+];
+
+/// Quantizes a row-major grid of intensities (0-255) into `levels` evenly spaced levels.
+/// Returns the level index (0..levels) for every cell, in the same order as the input.
+///
+/// With `DitherMode::None` each cell is bucketed independently, exactly like
+/// `Converter::intensity_to_char`. The other modes spread the quantization error
+/// so smooth gradients do not band.
+pub fn quantize(intensities: &[f32], width: usize, height: usize, levels: usize, mode: DitherMode) -> Vec<usize> {
+    debug_assert_eq!(intensities.len(), width * height);
+    if levels <= 1 {
+        return vec![0; intensities.len()];
+    }
+
+    let nearest = |value: f32| {
+        let index = nearest_level(value, levels);
+        (index, index as f32 * step(levels))
+    };
+    match mode {
+        DitherMode::None => intensities
+            .iter()
+            .map(|&v| (v.clamp(0.0, 255.0) as usize * (levels - 1)) / 255)
+            .collect(),
+        DitherMode::FloydSteinberg | DitherMode::Atkinson => {
+            diffuse(intensities, width, height, nearest, kernel(mode).expect("diffusion mode"))
+        }
+        DitherMode::Bayer => ordered(intensities, width, step(levels), nearest),
+    }
+}
+
+/// Quantizes a row-major grid of intensities (0-255) onto arbitrary target `levels`
+/// (0-255, in any order) and returns the index of the chosen level for every cell.
+/// Used for density-calibrated character sets, whose glyphs are not evenly spaced.
+pub fn quantize_to_levels(intensities: &[f32], width: usize, height: usize, levels: &[f32], mode: DitherMode) -> Vec<usize> {
+    debug_assert_eq!(intensities.len(), width * height);
+    if levels.len() <= 1 {
+        return vec![0; intensities.len()];
+    }
+
+    let nearest = |value: f32| {
+        let index = levels
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (*a - value).abs().total_cmp(&(*b - value).abs()))
+            .map_or(0, |(index, _)| index);
+        (index, levels[index])
+    };
+    match mode {
+        DitherMode::None => intensities.iter().map(|&v| nearest(v).0).collect(),
+        DitherMode::FloydSteinberg | DitherMode::Atkinson => {
+            diffuse(intensities, width, height, nearest, kernel(mode).expect("diffusion mode"))
+        }
+        DitherMode::Bayer => {
+            // Use the average spacing between levels as the threshold amplitude
+            let (min, max) = levels.iter().fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            ordered(intensities, width, (max - min) / (levels.len() - 1) as f32, nearest)
+        }
+    }
+}
+
+/// Error-diffusion kernel for a dither mode, or `None` for modes that do not diffuse.
+pub fn kernel(mode: DitherMode) -> Option<&'static [(i32, i32, f32)]> {
+    match mode {
+        DitherMode::FloydSteinberg => Some(&FLOYD_STEINBERG),
+        DitherMode::Atkinson => Some(&ATKINSON),
+        DitherMode::None | DitherMode::Bayer => None,
+    }
+}
+
+/// Bayer threshold for a cell position, in the range -0.5..0.5.
+pub fn bayer_threshold(x: usize, y: usize) -> f32 {
+    (BAYER_4X4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5
+}
+
+/// Size of one quantization step for the given number of levels.
+fn step(levels: usize) -> f32 {
+    255.0 / (levels - 1) as f32
+}
+
+/// Rounds an intensity to the nearest level index.
+fn nearest_level(value: f32, levels: usize) -> usize {
+    (value.clamp(0.0, 255.0) / step(levels)).round() as usize
+}
+
+/// Error-diffusion dithering: each cell is rounded to its nearest level (`nearest`
+/// returns the level index and value) and the remaining error is pushed to
+/// not-yet-visited neighbours using `kernel`.
+fn diffuse<N>(intensities: &[f32], width: usize, height: usize, nearest: N, kernel: &[(i32, i32, f32)]) -> Vec<usize>
+where
+    N: Fn(f32) -> (usize, f32),
+{
+    let mut buffer = intensities.to_vec();
+    let mut indices = Vec::with_capacity(buffer.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let value = buffer[y * width + x];
+            let (index, level) = nearest(value.clamp(0.0, 255.0));
+            let error = value - level;
+            indices.push(index);
+
+            for &(dx, dy, weight) in kernel {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx >= 0 && (nx as usize) < width && (ny as usize) < height {
+                    buffer[ny as usize * width + nx as usize] += error * weight;
+                }
+            }
+        }
+    }
+    indices
+}
+
+/// Ordered (Bayer) dithering: offsets each cell by a position-dependent threshold
+/// of up to half a quantization `step` before rounding. No state is carried between cells.
+fn ordered<N>(intensities: &[f32], width: usize, step: f32, nearest: N) -> Vec<usize>
+where
+    N: Fn(f32) -> (usize, f32),
+{
+    intensities
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let (x, y) = (i % width, i / width);
+            nearest((v + bayer_threshold(x, y) * step).clamp(0.0, 255.0)).0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_none_matches_bucketing() {
+        let values = [0.0, 127.0, 128.0, 255.0];
+        let indices = quantize(&values, 4, 1, 10, DitherMode::None);
+        assert_eq!(indices, vec![0, 4, 4, 9]);
+    }
+
+    #[test]
+    fn test_flat_field_on_a_level_keeps_its_glyph() {
+        for level in 0..5 {
+            let values = vec![(level as f32 * step(5)).ceil(); 16];
+            let plain = quantize(&values, 4, 4, 5, DitherMode::None);
+            assert!(plain.iter().all(|&i| i == level), "{:?}", plain);
+            for mode in [DitherMode::FloydSteinberg, DitherMode::Atkinson, DitherMode::Bayer] {
+                assert_eq!(quantize(&values, 4, 4, 5, mode), plain, "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_level() {
+        let indices = quantize(&[0.0, 255.0], 2, 1, 1, DitherMode::FloydSteinberg);
+        assert_eq!(indices, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_error_diffusion_preserves_average() {
+        // A flat mid-grey with two levels should come out roughly half on, half off.
+        let values = vec![128.0; 16 * 16];
+        for mode in [DitherMode::FloydSteinberg, DitherMode::Atkinson, DitherMode::Bayer] {
+            let indices = quantize(&values, 16, 16, 2, mode);
+            let on = indices.iter().filter(|&&i| i == 1).count();
+            assert!((96..=160).contains(&on), "{:?} produced {} lit cells", mode, on);
+        }
+    }
+
+    #[test]
+    fn test_extremes_are_stable() {
+        for mode in [DitherMode::FloydSteinberg, DitherMode::Atkinson, DitherMode::Bayer] {
+            assert!(quantize(&[0.0; 9], 3, 3, 5, mode).iter().all(|&i| i == 0));
+            assert!(quantize(&[255.0; 9], 3, 3, 5, mode).iter().all(|&i| i == 4));
+        }
+    }
+
+    #[test]
+    fn test_uneven_levels() {
+        // Levels crowded at the dark end: each cell picks the nearest level, not an even bucket
+        let levels = [0.0, 20.0, 40.0, 255.0];
+        assert_eq!(quantize_to_levels(&[0.0, 25.0, 120.0, 200.0], 4, 1, &levels, DitherMode::None), vec![0, 1, 2, 3]);
+        // Error diffusion still reproduces the average brightness
+        let indices = quantize_to_levels(&vec![128.0; 256], 16, 16, &levels, DitherMode::FloydSteinberg);
+        let mean = indices.iter().map(|&i| levels[i]).sum::<f32>() / indices.len() as f32;
+        assert!((mean - 128.0).abs() < 8.0, "mean was {}", mean);
+    }
+}
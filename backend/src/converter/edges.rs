@@ -0,0 +1,228 @@
+use crate::converter::config::EdgeDetector;
+
+/// Glyphs for each quantized edge orientation: horizontal, rising diagonal, vertical, falling diagonal.
+const DIRECTION_GLYPHS: [char; 4] = ['-', '/', '|', '\\'];
+
+/// Glyph for a horizontal edge in the lower half of a cell.
+const LOWER_EDGE_GLYPH: char = '_';
+
+/// Sobel kernels are scaled by this so magnitudes are comparable to a 0-255 intensity step.
+const SOBEL_NORMALIZATION: f32 = 4.0;
+
+/// Ratio of the low to the high Canny hysteresis threshold.
+const CANNY_LOW_RATIO: f32 = 0.5;
+
+/// Per-cell gradient information.
+struct Gradient {
+    magnitude: Vec<f32>,
+    /// Orientation bin (0..4) of the *edge* at each cell, indexing `DIRECTION_GLYPHS`.
+    direction: Vec<usize>,
+}
+
+/// Detects edges on a row-major luminance grid (0-255) and returns a direction glyph
+/// for every cell that lies on a strong edge, or `None` for flat regions.
+pub fn detect(luma: &[f32], width: usize, height: usize, detector: EdgeDetector, threshold: f32) -> Vec<Option<char>> {
+    debug_assert_eq!(luma.len(), width * height);
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    match detector {
+        EdgeDetector::Sobel => {
+            let gradient = sobel(luma, width, height);
+            (0..luma.len())
+                .map(|i| (gradient.magnitude[i] >= threshold).then(|| glyph(luma, width, height, i, gradient.direction[i])))
+                .collect()
+        }
+        EdgeDetector::Canny => canny(luma, width, height, threshold),
+    }
+}
+
+/// Reads a cell with edge clamping.
+fn at(luma: &[f32], width: usize, height: usize, x: i32, y: i32) -> f32 {
+    let x = x.clamp(0, width as i32 - 1) as usize;
+    let y = y.clamp(0, height as i32 - 1) as usize;
+    luma[y * width + x]
+}
+
+/// Glyph for an edge of orientation `direction` at cell `index`. A horizontal edge is drawn
+/// with `_` when the step below the cell is larger than the one above it, i.e. the edge runs
+/// along the bottom of the cell.
+fn glyph(luma: &[f32], width: usize, height: usize, index: usize, direction: usize) -> char {
+    if direction == 0 {
+        let (x, y) = ((index % width) as i32, (index / width) as i32);
+        let center = luma[index];
+        let below = (at(luma, width, height, x, y + 1) - center).abs();
+        let above = (center - at(luma, width, height, x, y - 1)).abs();
+        if below > above {
+            return LOWER_EDGE_GLYPH;
+        }
+    }
+    DIRECTION_GLYPHS[direction]
+}
+
+/// Computes Sobel gradient magnitude and quantized edge orientation.
+fn sobel(luma: &[f32], width: usize, height: usize) -> Gradient {
+    let mut magnitude = Vec::with_capacity(luma.len());
+    let mut direction = Vec::with_capacity(luma.len());
+
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let p = |dx: i32, dy: i32| at(luma, width, height, x + dx, y + dy);
+            let gx = (p(1, -1) + 2.0 * p(1, 0) + p(1, 1)) - (p(-1, -1) + 2.0 * p(-1, 0) + p(-1, 1));
+            let gy = (p(-1, 1) + 2.0 * p(0, 1) + p(1, 1)) - (p(-1, -1) + 2.0 * p(0, -1) + p(1, -1));
+
+            magnitude.push(gx.hypot(gy) / SOBEL_NORMALIZATION);
+            direction.push(edge_direction(gx, gy));
+        }
+    }
+    Gradient { magnitude, direction }
+}
+
+/// Quantizes a gradient vector into one of four edge orientations.
+/// The edge runs perpendicular to the gradient; image y grows downwards.
+fn edge_direction(gx: f32, gy: f32) -> usize {
+    // Gradient angle folded into [0, 180)
+    let angle = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+    match angle {
+        a if !(22.5..157.5).contains(&a) => 2, // horizontal gradient -> vertical edge
+        a if a < 67.5 => 1,                    // gradient down-right -> '/'
+        a if a < 112.5 => 0,                   // vertical gradient -> horizontal edge
+        _ => 3,                                // gradient down-left -> '\'
+    }
+}
+
+/// 3x3 Gaussian smoothing (sigma ~0.85) used to suppress noise before Canny.
+fn smooth(luma: &[f32], width: usize, height: usize) -> Vec<f32> {
+    const KERNEL: [[f32; 3]; 3] = [[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]];
+    let mut out = Vec::with_capacity(luma.len());
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let mut sum = 0.0;
+            for (ky, row) in KERNEL.iter().enumerate() {
+                for (kx, weight) in row.iter().enumerate() {
+                    sum += weight * at(luma, width, height, x + kx as i32 - 1, y + ky as i32 - 1);
+                }
+            }
+            out.push(sum / 16.0);
+        }
+    }
+    out
+}
+
+/// Canny edge detection: smoothing, Sobel, non-maximum suppression and hysteresis.
+/// `threshold` is the high hysteresis threshold; the low one is derived from it.
+fn canny(luma: &[f32], width: usize, height: usize, threshold: f32) -> Vec<Option<char>> {
+    let smoothed = smooth(luma, width, height);
+    let gradient = sobel(&smoothed, width, height);
+    let low = threshold * CANNY_LOW_RATIO;
+
+    // Non-maximum suppression along the gradient direction
+    let mut thin = vec![0.0f32; luma.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let m = gradient.magnitude[i];
+            // Neighbour offsets across the edge, i.e. along the gradient
+            let (dx, dy) = match gradient.direction[i] {
+                0 => (0, 1),  // horizontal edge: compare above/below
+                1 => (1, 1),  // '/' edge: gradient points down-right
+                2 => (1, 0),  // vertical edge: compare left/right
+                _ => (-1, 1), // '\' edge: gradient points down-left
+            };
+            let neighbour = |sx: i32, sy: i32| {
+                let nx = x as i32 + sx;
+                let ny = y as i32 + sy;
+                if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
+                    0.0
+                } else {
+                    gradient.magnitude[ny as usize * width + nx as usize]
+                }
+            };
+            if m >= neighbour(dx, dy) && m >= neighbour(-dx, -dy) {
+                thin[i] = m;
+            }
+        }
+    }
+
+    // Hysteresis: keep weak edges only if they connect to a strong one
+    let mut keep = vec![false; luma.len()];
+    let mut stack: Vec<usize> = (0..luma.len()).filter(|&i| thin[i] >= threshold).collect();
+    for &i in &stack {
+        keep[i] = true;
+    }
+    while let Some(i) = stack.pop() {
+        let (x, y) = ((i % width) as i32, (i / width) as i32);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
+                    continue;
+                }
+                let n = ny as usize * width + nx as usize;
+                if !keep[n] && thin[n] >= low {
+                    keep[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    (0..luma.len())
+        .map(|i| keep[i].then(|| glyph(&smoothed, width, height, i, gradient.direction[i])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a grid whose left half is black and right half white.
+    fn vertical_step(width: usize, height: usize) -> Vec<f32> {
+        (0..width * height)
+            .map(|i| if i % width < width / 2 { 0.0 } else { 255.0 })
+            .collect()
+    }
+
+    #[test]
+    fn test_flat_image_has_no_edges() {
+        let luma = vec![128.0; 25];
+        for detector in [EdgeDetector::Sobel, EdgeDetector::Canny] {
+            assert!(detect(&luma, 5, 5, detector, 32.0).iter().all(Option::is_none));
+        }
+    }
+
+    #[test]
+    fn test_vertical_edge_uses_pipe() {
+        let edges = detect(&vertical_step(8, 4), 8, 4, EdgeDetector::Sobel, 32.0);
+        assert_eq!(edges[4], Some('|'));
+        assert_eq!(edges[0], None);
+        assert_eq!(edges[7], None);
+    }
+
+    #[test]
+    fn test_horizontal_edge_sides() {
+        // Dark top half, bright bottom half: the edge is at the bottom of row 1 and the top of row 2
+        let luma: Vec<f32> = (0..4 * 4).map(|i| if i / 4 < 2 { 0.0 } else { 255.0 }).collect();
+        let edges = detect(&luma, 4, 4, EdgeDetector::Sobel, 32.0);
+        assert_eq!(edges[4], Some('_'));
+        assert_eq!(edges[8], Some('-'));
+        assert_eq!(edges[0], None);
+    }
+
+    #[test]
+    fn test_canny_thins_edges() {
+        let edges = detect(&vertical_step(8, 4), 8, 4, EdgeDetector::Canny, 32.0);
+        let row: Vec<_> = edges[8..16].iter().filter(|e| e.is_some()).collect();
+        assert!(!row.is_empty() && row.len() <= 2);
+        assert!(row.iter().all(|e| **e == Some('|')));
+    }
+
+    #[test]
+    fn test_diagonal_directions() {
+        assert_eq!(DIRECTION_GLYPHS[edge_direction(1.0, 0.0)], '|');
+        assert_eq!(DIRECTION_GLYPHS[edge_direction(0.0, 1.0)], '-');
+        assert_eq!(DIRECTION_GLYPHS[edge_direction(1.0, 1.0)], '/');
+        assert_eq!(DIRECTION_GLYPHS[edge_direction(-1.0, 1.0)], '\\');
+    }
+}
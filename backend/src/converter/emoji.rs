@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use crate::converter::{
+    config::{DitherMode, EmojiColor},
+    glyph::Glyph,
+    palette::Palette,
+};
+
+/// Built-in emoji with a representative color: roughly the average of the rendered
+/// glyph on common emoji fonts. Chosen so the colors cover the hue wheel and a gray ramp.
+pub const BUILTIN: &[(&str, [u8; 3])] = &[
+    ("⬛", [49, 55, 61]),
+    ("🌑", [102, 117, 127]),
+    ("🐘", [153, 170, 181]),
+    ("⬜", [230, 231, 232]),
+    ("🟥", [221, 46, 68]),
+    ("🌹", [190, 25, 49]),
+    ("🍑", [255, 136, 108]),
+    ("🟧", [244, 144, 12]),
+    ("🍞", [217, 158, 82]),
+    ("🟨", [253, 203, 88]),
+    ("🫒", [122, 140, 58]),
+    ("🍏", [166, 211, 136]),
+    ("🟩", [120, 177, 89]),
+    ("🌲", [62, 114, 29]),
+    ("🧊", [187, 221, 245]),
+    ("🟦", [85, 172, 238]),
+    ("🌊", [59, 136, 195]),
+    ("🌌", [44, 56, 110]),
+    ("🟪", [170, 142, 214]),
+    ("🍆", [116, 76, 157]),
+    ("🌸", [247, 181, 205]),
+    ("🟫", [193, 105, 79]),
+    ("🍫", [116, 60, 33]),
+];
+
+/// Emoji lookup by nearest color (in OKLab).
+pub struct EmojiTable {
+    glyphs: Vec<Glyph>,
+    palette: Palette,
+    /// Palette color back to the first emoji that has it.
+    by_color: HashMap<[u8; 3], usize>,
+}
+
+impl EmojiTable {
+    /// Builds the table from the user's entries followed by the built-in ones (if enabled).
+    /// User entries come first, so they win ties against built-in emoji of the same color.
+    pub fn new(custom: &[EmojiColor], include_builtin: bool) -> Self {
+        let builtin = BUILTIN
+            .iter()
+            .filter(|_| include_builtin)
+            .map(|&(emoji, rgb)| (Glyph::new(emoji), rgb));
+        let entries: Vec<(Glyph, [u8; 3])> = custom
+            .iter()
+            .map(|entry| (entry.emoji.clone(), entry.rgb))
+            .chain(builtin)
+            .collect();
+
+        let mut by_color = HashMap::new();
+        for (index, (_, rgb)) in entries.iter().enumerate() {
+            by_color.entry(*rgb).or_insert(index);
+        }
+        let (glyphs, colors) = entries.into_iter().unzip();
+        Self { glyphs, palette: Palette::new(colors), by_color }
+    }
+
+    /// Picks an emoji for every cell of a row-major color grid. Dithering spreads the
+    /// color error between cells like palette quantization does.
+    pub fn choose(&self, colors: &[[u8; 3]], width: usize, height: usize, dither: DitherMode) -> Vec<Glyph> {
+        let cells: Vec<Option<[u8; 3]>> = colors.iter().copied().map(Some).collect();
+        self.palette
+            .quantize(&cells, width, height, dither)
+            .into_iter()
+            .map(|rgb| {
+                let index = rgb.and_then(|rgb| self.by_color.get(&rgb).copied()).unwrap_or(0);
+                self.glyphs[index].clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_emoji_are_wide_single_glyphs() {
+        for &(emoji, _) in BUILTIN {
+            assert!(Glyph::parse(emoji).is_ok(), "{}", emoji);
+            assert_eq!(Glyph::new(emoji).width(), 2, "{}", emoji);
+        }
+    }
+
+    #[test]
+    fn test_nearest_emoji() {
+        let table = EmojiTable::new(&[], true);
+        let glyphs = table.choose(&[[230, 40, 60], [0, 0, 0], [255, 255, 255], [60, 120, 30]], 4, 1, DitherMode::None);
+        let emoji: Vec<&str> = glyphs.iter().map(Glyph::as_str).collect();
+        assert_eq!(emoji, vec!["🟥", "⬛", "⬜", "🌲"]);
+    }
+
+    #[test]
+    fn test_custom_entries() {
+        let custom = [
+            EmojiColor { emoji: Glyph::new("🔥"), rgb: [244, 144, 12] },
+            EmojiColor { emoji: Glyph::new("💧"), rgb: [93, 173, 236] },
+        ];
+        // Custom entries win ties with built-in colors
+        let table = EmojiTable::new(&custom, true);
+        assert_eq!(table.choose(&[[244, 144, 12]], 1, 1, DitherMode::None)[0].as_str(), "🔥");
+        // Without the built-in table, only custom emoji are used
+        let table = EmojiTable::new(&custom, false);
+        let glyphs = table.choose(&[[0, 0, 0], [0, 0, 255]], 2, 1, DitherMode::None);
+        assert!(glyphs.iter().all(|glyph| glyph.as_str() == "🔥" || glyph.as_str() == "💧"));
+        assert_eq!(glyphs[1].as_str(), "💧");
+    }
+}
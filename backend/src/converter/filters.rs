@@ -0,0 +1,175 @@
+use image::{imageops, Rgb, RgbImage};
+use crate::converter::{
+    config::{FilterKind, FilterStage, ImageFilter},
+    error::ConverterError,
+};
+
+/// Largest median radius accepted; the window grows with the square of it.
+const MAX_MEDIAN_RADIUS: u32 = 5;
+
+/// Checks the filter parameters.
+pub fn validate(filters: &[ImageFilter]) -> Result<(), ConverterError> {
+    for filter in filters {
+        let valid = match filter.kind {
+            FilterKind::UnsharpMask { sigma, amount, .. } => sigma.is_finite() && sigma > 0.0 && amount.is_finite() && amount >= 0.0,
+            FilterKind::GaussianBlur { sigma } => sigma.is_finite() && sigma > 0.0,
+            FilterKind::Median { radius } => (1..=MAX_MEDIAN_RADIUS).contains(&radius),
+            FilterKind::Posterize { levels } => levels >= 2,
+            FilterKind::EdgeEnhance { strength } => strength.is_finite() && strength >= 0.0,
+        };
+        if !valid {
+            return Err(ConverterError::InvalidParameter(format!("Invalid filter parameters: {:?}", filter.kind)));
+        }
+    }
+    Ok(())
+}
+
+/// Runs the filters of one stage, in list order.
+pub fn apply(img: RgbImage, filters: &[ImageFilter], stage: FilterStage) -> RgbImage {
+    filters
+        .iter()
+        .filter(|filter| filter.stage == stage)
+        .fold(img, |img, filter| apply_one(&img, filter.kind))
+}
+
+fn apply_one(img: &RgbImage, kind: FilterKind) -> RgbImage {
+    match kind {
+        FilterKind::UnsharpMask { sigma, amount, threshold } => unsharp_mask(img, sigma, amount, threshold),
+        FilterKind::GaussianBlur { sigma } => imageops::blur(img, sigma),
+        FilterKind::Median { radius } => median(img, radius),
+        FilterKind::Posterize { levels } => posterize(img, levels),
+        FilterKind::EdgeEnhance { strength } => edge_enhance(img, strength),
+    }
+}
+
+/// Adds `amount` times the difference between the image and its Gaussian blur. Channel
+/// differences of `threshold` or less are left alone, so flat areas and noise are not sharpened.
+fn unsharp_mask(img: &RgbImage, sigma: f32, amount: f32, threshold: u8) -> RgbImage {
+    let blurred = imageops::blur(img, sigma);
+    RgbImage::from_fn(img.width(), img.height(), |x, y| {
+        let (pixel, soft) = (img.get_pixel(x, y), blurred.get_pixel(x, y));
+        Rgb(std::array::from_fn(|i| {
+            let difference = pixel[i] as f32 - soft[i] as f32;
+            if difference.abs() <= threshold as f32 {
+                return pixel[i];
+            }
+            (pixel[i] as f32 + amount * difference).round().clamp(0.0, 255.0) as u8
+        }))
+    })
+}
+
+/// Per-channel median of the `(2 * radius + 1)`² window; removes speckle noise while
+/// keeping edges. Pixels past the border repeat the edge.
+fn median(img: &RgbImage, radius: u32) -> RgbImage {
+    let (width, height) = img.dimensions();
+    let radius = radius as i64;
+    let mut window = Vec::with_capacity(((2 * radius + 1) * (2 * radius + 1)) as usize);
+    RgbImage::from_fn(width, height, |x, y| {
+        Rgb(std::array::from_fn(|channel| {
+            window.clear();
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let sx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+                    let sy = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+                    window.push(img.get_pixel(sx, sy)[channel]);
+                }
+            }
+            let middle = window.len() / 2;
+            *window.select_nth_unstable(middle).1
+        }))
+    })
+}
+
+/// Rounds every channel to `levels` evenly spaced values.
+fn posterize(img: &RgbImage, levels: u8) -> RgbImage {
+    let steps = (levels - 1) as f32;
+    let lut: [u8; 256] = std::array::from_fn(|v| ((v as f32 / 255.0 * steps).round() * 255.0 / steps).round() as u8);
+    let mut out = img.clone();
+    out.pixels_mut().for_each(|pixel| pixel.0 = pixel.0.map(|v| lut[v as usize]));
+    out
+}
+
+/// Boosts each pixel's difference from its eight neighbours (a Laplacian). Strength 1
+/// matches the classic 3x3 edge-enhance kernel.
+fn edge_enhance(img: &RgbImage, strength: f32) -> RgbImage {
+    let (width, height) = img.dimensions();
+    RgbImage::from_fn(width, height, |x, y| {
+        let pixel = img.get_pixel(x, y);
+        Rgb(std::array::from_fn(|channel| {
+            let mut neighbours = 0.0;
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    let sx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+                    let sy = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+                    neighbours += img.get_pixel(sx, sy)[channel] as f32;
+                }
+            }
+            let center = pixel[channel] as f32;
+            let laplacian = 8.0 * center - neighbours;
+            (center + strength * laplacian / 2.0).round().clamp(0.0, 255.0) as u8
+        }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(width: u32, values: &[u8]) -> RgbImage {
+        RgbImage::from_fn(width, values.len() as u32 / width, |x, y| {
+            let v = values[(y * width + x) as usize];
+            Rgb([v, v, v])
+        })
+    }
+
+    fn row(img: &RgbImage) -> Vec<u8> {
+        img.pixels().map(|pixel| pixel[0]).collect()
+    }
+
+    fn filter(kind: FilterKind, stage: FilterStage) -> ImageFilter {
+        ImageFilter { kind, stage }
+    }
+
+    #[test]
+    fn test_median_removes_speckles() {
+        let img = gray(5, &[10, 10, 250, 10, 10]);
+        assert_eq!(row(&median(&img, 1)), vec![10; 5]);
+    }
+
+    #[test]
+    fn test_posterize() {
+        let img = gray(4, &[0, 100, 150, 255]);
+        assert_eq!(row(&posterize(&img, 2)), vec![0, 0, 255, 255]);
+        assert_eq!(row(&posterize(&img, 3)), vec![0, 128, 128, 255]);
+    }
+
+    #[test]
+    fn test_sharpening_increases_edge_contrast() {
+        let img = gray(10, &[50, 50, 50, 50, 50, 200, 200, 200, 200, 200]);
+        for sharpened in [unsharp_mask(&img, 1.0, 1.0, 0), edge_enhance(&img, 1.0)] {
+            let values = row(&sharpened);
+            assert!(values[4] < 50 && values[5] > 200, "{:?}", values);
+            assert_eq!((values[0], values[9]), (50, 200));
+        }
+        // Differences within the threshold are left untouched
+        assert_eq!(row(&unsharp_mask(&img, 1.0, 1.0, 255)), row(&img));
+    }
+
+    #[test]
+    fn test_stages_run_in_order() {
+        let img = gray(3, &[0, 90, 255]);
+        let filters = [
+            filter(FilterKind::Posterize { levels: 2 }, FilterStage::AfterResize),
+            filter(FilterKind::GaussianBlur { sigma: 3.0 }, FilterStage::BeforeResize),
+            filter(FilterKind::Posterize { levels: 3 }, FilterStage::AfterResize),
+        ];
+        // Posterizing to 2 then 3 levels keeps the 2-level result
+        assert_eq!(row(&apply(img, &filters, FilterStage::AfterResize)), vec![0, 0, 255]);
+        assert!(validate(&filters).is_ok());
+        assert!(validate(&[filter(FilterKind::GaussianBlur { sigma: 0.0 }, FilterStage::BeforeResize)]).is_err());
+        assert!(validate(&[filter(FilterKind::Median { radius: 0 }, FilterStage::BeforeResize)]).is_err());
+    }
+}
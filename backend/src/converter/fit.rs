@@ -0,0 +1,184 @@
+use crate::converter::config::{CropRect, FitMode, Gravity};
+
+/// Sizing options that decide the output grid.
+#[derive(Debug, Clone, Copy)]
+pub struct FitSettings {
+    pub output_width: u32,
+    pub output_height: Option<u32>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub aspect_ratio_correction: f32,
+    pub fit: FitMode,
+    pub gravity: Gravity,
+    pub no_upscale: bool,
+}
+
+/// Where the image goes in the output grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// Part of the source image to render (`cover` only).
+    pub crop: Option<CropRect>,
+    /// Size of the rendered image, in cells.
+    pub content_width: u32,
+    pub content_height: u32,
+    /// Position of the rendered image in the grid; the rest is padding (`contain` only).
+    pub left: u32,
+    pub top: u32,
+    /// Size of the whole grid, in cells.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Gravity {
+    /// Horizontal and vertical anchor, in halves: 0 is left/top, 1 the center, 2 right/bottom.
+    fn anchor(self) -> (u32, u32) {
+        match self {
+            Gravity::Center => (1, 1),
+            Gravity::Top => (1, 0),
+            Gravity::Bottom => (1, 2),
+            Gravity::Left => (0, 1),
+            Gravity::Right => (2, 1),
+            Gravity::TopLeft => (0, 0),
+            Gravity::TopRight => (2, 0),
+            Gravity::BottomLeft => (0, 2),
+            Gravity::BottomRight => (2, 2),
+        }
+    }
+}
+
+/// Offset that places `inner` inside `outer` at the anchor.
+fn place(outer: u32, inner: u32, anchor: u32) -> u32 {
+    outer.saturating_sub(inner) * anchor / 2
+}
+
+/// Lays out an `image_width` x `image_height` image. Returns `None` if the output would
+/// have no rows.
+///
+/// The target box is `output_width` by `output_height` (or the height that keeps the
+/// aspect ratio), shrunk to fit `max_width`/`max_height`. One cell is one source pixel wide
+/// and `1 / aspect_ratio_correction` pixels tall at scale 1, which `no_upscale` does not exceed.
+pub fn layout(image_width: u32, image_height: u32, settings: FitSettings) -> Option<Layout> {
+    // Natural size of the image in cells at scale 1
+    let natural_width = image_width as f32;
+    let natural_height = image_height as f32 * settings.aspect_ratio_correction;
+
+    let box_width = settings.output_width;
+    let box_height = settings
+        .output_height
+        .unwrap_or(((box_width as f32 * image_height as f32 / image_width as f32) * settings.aspect_ratio_correction) as u32);
+    if box_height == 0 {
+        return None;
+    }
+
+    let mut limit = [
+        settings.max_width.map(|max| max as f32 / box_width as f32),
+        settings.max_height.map(|max| max as f32 / box_height as f32),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(1.0, f32::min);
+    // Stretch and cover fill the whole box, so the box itself must not outgrow the image
+    if settings.no_upscale && settings.fit != FitMode::Contain {
+        limit = limit.min(natural_width / box_width as f32).min(natural_height / box_height as f32);
+    }
+    let shrink = |size: u32| ((size as f32 * limit) as u32).max(1);
+    let (width, height) = if limit < 1.0 { (shrink(box_width), shrink(box_height)) } else { (box_width, box_height) };
+
+    let (anchor_x, anchor_y) = settings.gravity.anchor();
+    let full = Layout { crop: None, content_width: width, content_height: height, left: 0, top: 0, width, height };
+    let layout = match settings.fit {
+        FitMode::Stretch => full,
+        FitMode::Contain => {
+            let mut scale = (width as f32 / natural_width).min(height as f32 / natural_height);
+            if settings.no_upscale {
+                scale = scale.min(1.0);
+            }
+            let content_width = ((natural_width * scale).round() as u32).clamp(1, width);
+            let content_height = ((natural_height * scale).round() as u32).clamp(1, height);
+            Layout {
+                content_width,
+                content_height,
+                left: place(width, content_width, anchor_x),
+                top: place(height, content_height, anchor_y),
+                ..full
+            }
+        }
+        FitMode::Cover => {
+            let scale = (width as f32 / natural_width).max(height as f32 / natural_height);
+            let crop_width = ((width as f32 / scale).round() as u32).clamp(1, image_width);
+            let crop_height = ((height as f32 / scale / settings.aspect_ratio_correction).round() as u32).clamp(1, image_height);
+            let crop = CropRect {
+                x: place(image_width, crop_width, anchor_x),
+                y: place(image_height, crop_height, anchor_y),
+                width: crop_width,
+                height: crop_height,
+            };
+            let uncropped = crop.width == image_width && crop.height == image_height;
+            Layout { crop: (!uncropped).then_some(crop), ..full }
+        }
+    };
+    Some(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(fit: FitMode) -> FitSettings {
+        FitSettings {
+            output_width: 40,
+            output_height: Some(20),
+            max_width: None,
+            max_height: None,
+            aspect_ratio_correction: 0.5,
+            fit,
+            gravity: Gravity::Center,
+            no_upscale: false,
+        }
+    }
+
+    #[test]
+    fn test_stretch_keeps_the_box() {
+        let stretched = layout(100, 100, settings(FitMode::Stretch)).unwrap();
+        assert_eq!((stretched.width, stretched.height, stretched.content_width, stretched.content_height), (40, 20, 40, 20));
+        // Without an output height the aspect ratio decides it
+        let natural = layout(100, 200, FitSettings { output_height: None, ..settings(FitMode::Stretch) }).unwrap();
+        assert_eq!(natural.height, 40);
+        assert!(layout(100, 1, FitSettings { output_height: None, ..settings(FitMode::Stretch) }).is_none());
+    }
+
+    #[test]
+    fn test_contain_pads_with_gravity() {
+        // With a 0.5 aspect correction a square image is 2:1 in cells, like the box
+        let square = layout(100, 100, settings(FitMode::Contain)).unwrap();
+        assert_eq!((square.content_width, square.content_height, square.left, square.top), (40, 20, 0, 0));
+        let tall = layout(100, 400, settings(FitMode::Contain)).unwrap();
+        assert_eq!((tall.content_width, tall.content_height), (10, 20));
+        assert_eq!(tall.left, 15);
+        let right = layout(100, 400, FitSettings { gravity: Gravity::Right, ..settings(FitMode::Contain) }).unwrap();
+        assert_eq!(right.left, 30);
+    }
+
+    #[test]
+    fn test_cover_crops_with_gravity() {
+        let wide = layout(400, 100, settings(FitMode::Cover)).unwrap();
+        assert_eq!(wide.crop, Some(CropRect { x: 150, y: 0, width: 100, height: 100 }));
+        assert_eq!((wide.content_width, wide.content_height), (40, 20));
+        let top = layout(100, 400, FitSettings { gravity: Gravity::Top, ..settings(FitMode::Cover) }).unwrap();
+        assert_eq!(top.crop, Some(CropRect { x: 0, y: 0, width: 100, height: 100 }));
+    }
+
+    #[test]
+    fn test_max_size_and_no_upscale() {
+        let capped = layout(100, 100, FitSettings { max_height: Some(10), ..settings(FitMode::Stretch) }).unwrap();
+        assert_eq!((capped.width, capped.height), (20, 10));
+
+        let tiny = FitSettings { no_upscale: true, ..settings(FitMode::Stretch) };
+        let stretched = layout(8, 8, tiny).unwrap();
+        assert_eq!((stretched.width, stretched.height), (8, 4));
+        // Contain keeps the box and pads around the unscaled image
+        let contained = layout(8, 8, FitSettings { fit: FitMode::Contain, ..tiny }).unwrap();
+        assert_eq!((contained.width, contained.height, contained.content_width, contained.content_height), (40, 20, 8, 4));
+        assert_eq!((contained.left, contained.top), (16, 8));
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// One displayed glyph: a single grapheme cluster. Most glyphs are one `char`, but
+/// combining sequences, emoji with modifiers and ZWJ sequences span several code points.
+/// The empty glyph marks the second column covered by a wide glyph.
+///
+/// Serialized as a plain string, so single characters look exactly like a `char`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Glyph(Box<str>);
+
+impl Glyph {
+    /// Wraps a grapheme cluster. The string is not checked; use `parse` for user input.
+    pub fn new(cluster: &str) -> Self {
+        Self(cluster.into())
+    }
+
+    /// Accepts a string only if it is exactly one grapheme cluster.
+    pub fn parse(cluster: &str) -> Result<Self, String> {
+        let mut graphemes = cluster.graphemes(true);
+        match (graphemes.next(), graphemes.next()) {
+            (Some(_), None) => Ok(Self::new(cluster)),
+            _ => Err(format!("glyph {:?} must be exactly one grapheme cluster", cluster)),
+        }
+    }
+
+    /// Splits a string into its grapheme clusters.
+    pub fn split(text: &str) -> Vec<Self> {
+        text.graphemes(true).map(Self::new).collect()
+    }
+
+    /// The placeholder for the second column of a wide glyph.
+    pub fn continuation() -> Self {
+        Self::new("")
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The glyph as a single `char`, if it is one code point.
+    pub fn as_char(&self) -> Option<char> {
+        let mut chars = self.as_str().chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Some(ch),
+            _ => None,
+        }
+    }
+
+    /// Number of terminal columns the glyph occupies: 2 for wide glyphs (CJK, emoji),
+    /// 1 otherwise, and 0 for a continuation.
+    pub fn width(&self) -> usize {
+        let cluster = self.as_str();
+        if cluster.is_empty() {
+            return 0;
+        }
+        cluster.width().clamp(1, 2)
+    }
+}
+
+/// A space, the blank glyph.
+impl Default for Glyph {
+    fn default() -> Self {
+        Self::from(' ')
+    }
+}
+
+impl From<char> for Glyph {
+    fn from(ch: char) -> Self {
+        Self::new(ch.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl PartialEq<char> for Glyph {
+    fn eq(&self, other: &char) -> bool {
+        self.as_char() == Some(*other)
+    }
+}
+
+impl fmt::Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Serialize for Glyph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Accepts one grapheme cluster, or the empty continuation of a wide glyph.
+impl<'de> Deserialize<'de> for Glyph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cluster = String::deserialize(deserializer)?;
+        if cluster.is_empty() {
+            return Ok(Self::continuation());
+        }
+        Self::parse(&cluster).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widths() {
+        assert_eq!(Glyph::from('a').width(), 1);
+        assert_eq!(Glyph::from('漢').width(), 2);
+        assert_eq!(Glyph::from('ｱ').width(), 1);
+        assert_eq!(Glyph::new("e\u{301}").width(), 1);
+        assert_eq!(Glyph::new("❤\u{FE0F}").width(), 2);
+        assert_eq!(Glyph::new("👨\u{200D}👩\u{200D}👧\u{200D}👦").width(), 2);
+        assert_eq!(Glyph::continuation().width(), 0);
+    }
+
+    #[test]
+    fn test_parse_and_split() {
+        assert!(Glyph::parse("👍🏽").is_ok());
+        assert!(Glyph::parse("ab").is_err());
+        assert!(Glyph::parse("").is_err());
+        let glyphs = Glyph::split(" e\u{301}🇯🇵");
+        assert_eq!(glyphs.len(), 3);
+        assert_eq!(glyphs[2].as_str(), "🇯🇵");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let family = "👨\u{200D}👩\u{200D}👧\u{200D}👦";
+        for cluster in ["#", "e\u{301}", family, ""] {
+            let glyph = Glyph::new(cluster);
+            assert_eq!(glyph.as_str(), cluster);
+            let json = serde_json::to_string(&glyph).unwrap();
+            assert_eq!(serde_json::from_str::<Glyph>(&json).unwrap(), glyph);
+        }
+        assert_eq!(Glyph::from('#'), '#');
+        assert_eq!(Glyph::new(family).as_char(), None);
+        assert!(serde_json::from_str::<Glyph>("\"ab\"").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::converter::{
+    config::GlyphMetric,
+    error::ConverterError,
+    font::{self, GLYPH_HEIGHT, GLYPH_WIDTH},
+    glyph::Glyph,
+};
+
+/// Number of pixels in one cell block / glyph bitmap.
+pub const BLOCK_LEN: usize = GLYPH_WIDTH * GLYPH_HEIGHT;
+
+/// SSIM stabilizing constants for an 8-bit dynamic range.
+const SSIM_C1: f32 = (0.01 * 255.0) * (0.01 * 255.0);
+const SSIM_C2: f32 = (0.03 * 255.0) * (0.03 * 255.0);
+
+/// Picks characters by comparing each cell's pixel block against rasterized glyphs.
+pub struct GlyphMatcher {
+    /// Glyph bitmaps scaled to 0-255 (ink is bright), in character set order.
+    glyphs: Vec<[f32; BLOCK_LEN]>,
+    metric: GlyphMetric,
+}
+
+impl GlyphMatcher {
+    /// Rasterizes every character in `character_set` with the bundled font.
+    /// Fails if a character has no bitmap, since it could never be matched fairly.
+    pub fn new(character_set: &[Glyph], metric: GlyphMetric) -> Result<Self, ConverterError> {
+        let glyphs = character_set
+            .iter()
+            .map(|glyph| {
+                glyph.as_char().and_then(font::glyph_bitmap)
+                    .map(|bitmap| soften(&bitmap.map(|coverage| coverage * 255.0)))
+                    .ok_or_else(|| ConverterError::InvalidParameter(
+                        format!("Glyph matching has no bitmap for character {:?}", glyph)
+                    ))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { glyphs, metric })
+    }
+
+    /// Returns the index of the glyph that best matches a row-major block of
+    /// `GLYPH_WIDTH x GLYPH_HEIGHT` intensities (0-255).
+    pub fn best_match(&self, block: &[f32]) -> usize {
+        debug_assert_eq!(block.len(), BLOCK_LEN);
+        let mut raw = [0.0; BLOCK_LEN];
+        raw.copy_from_slice(block);
+        let block = soften(&raw);
+        let score = |glyph: &[f32; BLOCK_LEN]| match self.metric {
+            GlyphMetric::Mse => -mse(&block, glyph),
+            GlyphMetric::Ssim => ssim(&block, glyph),
+        };
+
+        let mut best = (0, f32::NEG_INFINITY);
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            let s = score(glyph);
+            if s > best.1 {
+                best = (index, s);
+            }
+        }
+        best.0
+    }
+}
+
+/// Applies a 3x3 box blur to a block. Comparing softened blocks against softened glyphs
+/// makes ink coverage count in smooth regions instead of only hard-edged structure.
+fn soften(glyph: &[f32; BLOCK_LEN]) -> [f32; BLOCK_LEN] {
+    let mut out = [0.0; BLOCK_LEN];
+    for y in 0..GLYPH_HEIGHT as i32 {
+        for x in 0..GLYPH_WIDTH as i32 {
+            let (mut sum, mut count) = (0.0, 0.0);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx >= 0 && ny >= 0 && nx < GLYPH_WIDTH as i32 && ny < GLYPH_HEIGHT as i32 {
+                        sum += glyph[ny as usize * GLYPH_WIDTH + nx as usize];
+                        count += 1.0;
+                    }
+                }
+            }
+            out[y as usize * GLYPH_WIDTH + x as usize] = sum / count;
+        }
+    }
+    out
+}
+
+/// Mean squared error between two equally sized blocks.
+fn mse(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>() / a.len() as f32
+}
+
+/// Structural similarity index of two equally sized blocks (single window).
+fn ssim(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len() as f32;
+    let mean_a = a.iter().sum::<f32>() / n;
+    let mean_b = b.iter().sum::<f32>() / n;
+
+    let (mut var_a, mut var_b, mut covariance) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (da, db) = (x - mean_a, y - mean_b);
+        var_a += da * da;
+        var_b += db * db;
+        covariance += da * db;
+    }
+    var_a /= n;
+    var_b /= n;
+    covariance /= n;
+
+    ((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * covariance + SSIM_C2))
+        / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1) * (var_a + var_b + SSIM_C2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders a character from the bundled font as a 0-255 block.
+    fn block_of(ch: char) -> Vec<f32> {
+        font::glyph_bitmap(ch).unwrap().iter().map(|c| c * 255.0).collect()
+    }
+
+    #[test]
+    fn test_exact_glyph_is_matched() {
+        let chars = [' ', '.', '-', '|', '/', '\\', '#', '@'];
+        let glyphs: Vec<Glyph> = chars.iter().map(|&ch| ch.into()).collect();
+        for metric in [GlyphMetric::Mse, GlyphMetric::Ssim] {
+            let matcher = GlyphMatcher::new(&glyphs, metric).unwrap();
+            for (i, &ch) in chars.iter().enumerate() {
+                assert_eq!(matcher.best_match(&block_of(ch)), i, "{:?} with {:?}", ch, metric);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat_blocks() {
+        let matcher = GlyphMatcher::new(&Glyph::split(" █"), GlyphMetric::Mse).unwrap();
+        assert_eq!(matcher.best_match(&[0.0; BLOCK_LEN]), 0);
+        assert_eq!(matcher.best_match(&[255.0; BLOCK_LEN]), 1);
+    }
+
+    #[test]
+    fn test_missing_glyph_is_rejected() {
+        assert!(GlyphMatcher::new(&Glyph::split("a\u{263A}"), GlyphMetric::Ssim).is_err());
+    }
+}
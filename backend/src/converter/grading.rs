@@ -0,0 +1,157 @@
+/// Luminance weights shared by the grading matrices (the ones CSS filters use), so
+/// saturation and hue changes keep each color's brightness.
+const LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// How far `temperature` 1.0 scales the red and blue channels.
+const TEMPERATURE_SCALE: f32 = 0.25;
+
+/// Color grading parameters. Every step is linear in RGB, so they combine into one matrix.
+#[derive(Debug, Clone, Copy)]
+pub struct GradingSettings {
+    /// 0 is grayscale, 1 leaves colors unchanged, above 1 boosts them.
+    pub saturation: f32,
+    /// Hue rotation in degrees.
+    pub hue_shift: f32,
+    /// -1 (cool, blue) to 1 (warm, orange).
+    pub temperature: f32,
+    /// Amount (0-1) of the sepia tone.
+    pub sepia: f32,
+    /// Tint color blended in, scaled by each pixel's luminance.
+    pub tint_color: [u8; 3],
+    /// Amount (0-1) of `tint_color`.
+    pub tint_strength: f32,
+}
+
+/// A 3x3 matrix applied to linear combinations of the RGB channels.
+pub type Matrix = [[f32; 3]; 3];
+
+const IDENTITY: Matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+impl GradingSettings {
+    /// Whether the settings change any color.
+    pub fn is_identity(&self) -> bool {
+        self.saturation == 1.0
+            && self.hue_shift % 360.0 == 0.0
+            && self.temperature == 0.0
+            && self.sepia == 0.0
+            && self.tint_strength == 0.0
+    }
+
+    /// The combined grading matrix: saturation, hue rotation, temperature, sepia, then tint.
+    pub fn matrix(&self) -> Matrix {
+        let s = self.saturation;
+        let saturate: Matrix = std::array::from_fn(|row| {
+            std::array::from_fn(|col| LUMA[col] * (1.0 - s) + if row == col { s } else { 0.0 })
+        });
+
+        let (sin, cos) = self.hue_shift.to_radians().sin_cos();
+        let hue: Matrix = [
+            [0.213 + cos * 0.787 - sin * 0.213, 0.715 - cos * 0.715 - sin * 0.715, 0.072 - cos * 0.072 + sin * 0.928],
+            [0.213 - cos * 0.213 + sin * 0.143, 0.715 + cos * 0.285 + sin * 0.140, 0.072 - cos * 0.072 - sin * 0.283],
+            [0.213 - cos * 0.213 - sin * 0.787, 0.715 - cos * 0.715 + sin * 0.715, 0.072 + cos * 0.928 + sin * 0.072],
+        ];
+
+        let t = self.temperature * TEMPERATURE_SCALE;
+        let temperature: Matrix = [[1.0 + t, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0 - t]];
+
+        let sepia = blend(IDENTITY, [
+            [0.393, 0.769, 0.189],
+            [0.349, 0.686, 0.168],
+            [0.272, 0.534, 0.131],
+        ], self.sepia);
+
+        // Luminance times the tint color: a rank-one matrix
+        let tinted: Matrix = std::array::from_fn(|row| {
+            std::array::from_fn(|col| self.tint_color[row] as f32 / 255.0 * LUMA[col])
+        });
+        let tint = blend(IDENTITY, tinted, self.tint_strength);
+
+        [saturate, hue, temperature, sepia, tint]
+            .into_iter()
+            .fold(IDENTITY, |combined, step| multiply(step, combined))
+    }
+}
+
+/// Applies a grading matrix to an 8-bit color.
+pub fn apply(matrix: &Matrix, rgb: [u8; 3]) -> [u8; 3] {
+    let rgb = rgb.map(|v| v as f32);
+    std::array::from_fn(|row| {
+        let value: f32 = (0..3).map(|col| matrix[row][col] * rgb[col]).sum();
+        value.round().clamp(0.0, 255.0) as u8
+    })
+}
+
+/// Color at `intensity` (0-255) on a ramp of evenly spaced color stops, from dark to bright.
+pub fn gradient(stops: &[[u8; 3]], intensity: u8) -> [u8; 3] {
+    if stops.len() < 2 {
+        return stops.first().copied().unwrap_or([intensity; 3]);
+    }
+    let position = intensity as f32 / 255.0 * (stops.len() - 1) as f32;
+    let index = (position as usize).min(stops.len() - 2);
+    let t = position - index as f32;
+    let (from, to) = (stops[index], stops[index + 1]);
+    std::array::from_fn(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8)
+}
+
+fn blend(from: Matrix, to: Matrix, amount: f32) -> Matrix {
+    std::array::from_fn(|row| std::array::from_fn(|col| from[row][col] + (to[row][col] - from[row][col]) * amount))
+}
+
+fn multiply(a: Matrix, b: Matrix) -> Matrix {
+    std::array::from_fn(|row| std::array::from_fn(|col| (0..3).map(|k| a[row][k] * b[k][col]).sum()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEUTRAL: GradingSettings = GradingSettings {
+        saturation: 1.0,
+        hue_shift: 0.0,
+        temperature: 0.0,
+        sepia: 0.0,
+        tint_color: [255, 0, 0],
+        tint_strength: 0.0,
+    };
+
+    #[test]
+    fn test_neutral_settings_keep_colors() {
+        assert!(NEUTRAL.is_identity());
+        let matrix = NEUTRAL.matrix();
+        for rgb in [[0, 0, 0], [255, 255, 255], [200, 30, 90]] {
+            assert_eq!(apply(&matrix, rgb), rgb);
+        }
+        let full_turn = GradingSettings { hue_shift: 360.0, ..NEUTRAL };
+        assert!(full_turn.is_identity());
+        assert_eq!(apply(&full_turn.matrix(), [200, 30, 90]), [200, 30, 90]);
+    }
+
+    #[test]
+    fn test_saturation_and_hue() {
+        let gray = apply(&GradingSettings { saturation: 0.0, ..NEUTRAL }.matrix(), [200, 30, 90]);
+        assert!(gray[0] == gray[1] && gray[1] == gray[2], "{:?}", gray);
+        // A third of a turn moves red towards green
+        let shifted = apply(&GradingSettings { hue_shift: 120.0, ..NEUTRAL }.matrix(), [255, 0, 0]);
+        assert!(shifted[1] > shifted[0] && shifted[1] > shifted[2], "{:?}", shifted);
+    }
+
+    #[test]
+    fn test_warm_sepia_and_tint() {
+        let warm = apply(&GradingSettings { temperature: 1.0, ..NEUTRAL }.matrix(), [128, 128, 128]);
+        assert!(warm[0] > 128 && warm[2] < 128);
+        let sepia = apply(&GradingSettings { sepia: 1.0, ..NEUTRAL }.matrix(), [128, 128, 128]);
+        assert!(sepia[0] > sepia[1] && sepia[1] > sepia[2]);
+        let tinted = apply(&GradingSettings { tint_strength: 1.0, ..NEUTRAL }.matrix(), [255, 255, 255]);
+        assert_eq!(tinted, [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_gradient_map() {
+        let duotone = [[20, 0, 80], [255, 200, 0]];
+        assert_eq!(gradient(&duotone, 0), [20, 0, 80]);
+        assert_eq!(gradient(&duotone, 255), [255, 200, 0]);
+        let ramp = [[0, 0, 0], [255, 0, 0], [255, 255, 255]];
+        assert_eq!(gradient(&ramp, 128), [255, 1, 1]);
+        assert_eq!(gradient(&ramp, 64), [128, 0, 0]);
+    }
+}
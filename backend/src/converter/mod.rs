@@ -2,7 +2,30 @@ pub mod config;
 pub mod core;
 pub mod ascii_pixel;
 pub mod error;
+pub mod dither;
+pub mod edges;
+pub mod font;
+pub mod glyph_match;
+pub mod mosaic;
+pub mod braille;
+pub mod resample;
+pub mod color;
+pub mod contrast;
+pub mod palette;
+pub mod preprocess;
+pub mod animation;
+pub mod charsets;
+pub mod density;
+pub mod tone;
+pub mod glyph;
+pub mod emoji;
+pub mod threshold;
+pub mod filters;
+pub mod grading;
+pub mod fit;
+pub mod budget;
 
+pub use animation::AsciiAnimation;
 pub use ascii_pixel::AsciiPixel;
 pub use config::ConverterConfig;
 pub use core::Converter;
\ No newline at end of file
@@ -0,0 +1,204 @@
+use crate::converter::{ascii_pixel::AsciiPixel, config::{DitherMode, RenderMode}, dither};
+
+/// Quadrant glyphs indexed by a 4-bit mask (1 = upper left, 2 = upper right, 4 = lower left, 8 = lower right).
+const QUADRANT_GLYPHS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// Half-block glyphs indexed by a 2-bit mask (1 = top, 2 = bottom).
+const HALF_BLOCK_GLYPHS: [char; 4] = [' ', '▀', '▄', '█'];
+
+/// Layout of sub-pixels inside one character cell for a mosaic mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubCells {
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl SubCells {
+    /// Number of sub-pixels per cell.
+    pub fn count(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+}
+
+/// Returns the sub-pixel layout for a mosaic render mode, or `None` for other modes.
+pub fn sub_cells(mode: RenderMode) -> Option<SubCells> {
+    match mode {
+        RenderMode::HalfBlock => Some(SubCells { columns: 1, rows: 2 }),
+        RenderMode::Quadrant => Some(SubCells { columns: 2, rows: 2 }),
+        RenderMode::Sextant => Some(SubCells { columns: 2, rows: 3 }),
+        _ => None,
+    }
+}
+
+/// Maps a sub-pixel bit mask (bit `i` = sub-pixel `i` in row-major order) to its block glyph.
+fn glyph_for(layout: SubCells, mask: usize) -> char {
+    match layout.count() {
+        2 => HALF_BLOCK_GLYPHS[mask],
+        4 => QUADRANT_GLYPHS[mask],
+        _ => sextant_glyph(mask),
+    }
+}
+
+/// Maps a 6-bit sextant mask (two columns, three rows) to a character.
+/// The Symbols for Legacy Computing block (U+1FB00..) skips the masks that
+/// already exist as space, left/right half blocks and the full block.
+fn sextant_glyph(mask: usize) -> char {
+    match mask {
+        0 => ' ',
+        21 => '▌',
+        42 => '▐',
+        63 => '█',
+        _ => {
+            let skipped = (mask > 21) as usize + (mask > 42) as usize;
+            char::from_u32(0x1FB00 + (mask - 1 - skipped) as u32).unwrap_or('?')
+        }
+    }
+}
+
+/// Renders a grayscale mosaic. `luma` is the sub-pixel grid (row-major,
+/// `width * columns` by `height * rows`); each sub-pixel is thresholded to on/off,
+/// optionally with dithering, and bright sub-pixels become ink.
+pub fn render_gray(luma: &[f32], width: u32, height: u32, layout: SubCells, dither_mode: DitherMode) -> Vec<Vec<AsciiPixel>> {
+    let sub_width = (width * layout.columns) as usize;
+    let sub_height = (height * layout.rows) as usize;
+    let bits = match dither_mode {
+        DitherMode::None => luma.iter().map(|&v| (v >= 128.0) as usize).collect(),
+        _ => dither::quantize(luma, sub_width, sub_height, 2, dither_mode),
+    };
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let mut mask = 0;
+                    for (i, (sx, sy)) in positions(x, y, layout).enumerate() {
+                        if bits[sy * sub_width + sx] == 1 {
+                            mask |= 1 << i;
+                        }
+                    }
+                    AsciiPixel { ch: glyph_for(layout, mask).into(), rgb: None, bg: None, alpha: None }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Renders a color mosaic. `get_color` returns the sub-pixel color at a sub-pixel
+/// coordinate. Each cell is split into the two-color partition with the smallest
+/// squared error; the glyph draws the foreground group over the background group.
+pub fn render_color<F>(width: u32, height: u32, layout: SubCells, get_color: F) -> Vec<Vec<AsciiPixel>>
+where
+    F: Fn(u32, u32) -> [u8; 3],
+{
+    let count = layout.count();
+    let mut colors = vec![[0.0f32; 3]; count];
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    for (i, (sx, sy)) in positions(x, y, layout).enumerate() {
+                        colors[i] = get_color(sx as u32, sy as u32).map(|c| c as f32);
+                    }
+                    let (mask, fg, bg) = best_partition(&colors);
+                    AsciiPixel { ch: glyph_for(layout, mask).into(), rgb: Some(fg), bg: Some(bg), alpha: None }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Sub-pixel coordinates of a cell in row-major order.
+fn positions(x: u32, y: u32, layout: SubCells) -> impl Iterator<Item = (usize, usize)> {
+    (0..layout.rows).flat_map(move |row| {
+        (0..layout.columns).map(move |col| {
+            ((x * layout.columns + col) as usize, (y * layout.rows + row) as usize)
+        })
+    })
+}
+
+/// Finds the foreground mask and the two mean colors that best represent the sub-pixels.
+/// Masks and their complements describe the same split, so only masks containing
+/// sub-pixel 0 are tried. The full mask is tried first so uniform cells become a full block.
+fn best_partition(colors: &[[f32; 3]]) -> (usize, [u8; 3], [u8; 3]) {
+    let count = colors.len();
+    let full = (1 << count) - 1;
+    let mut best = (full, [0.0; 3], [0.0; 3], f32::INFINITY);
+
+    for mask in (1..=full).rev().step_by(2) {
+        let fg = mean(colors, |i| mask & (1 << i) != 0);
+        let bg = if mask == full { fg } else { mean(colors, |i| mask & (1 << i) == 0) };
+
+        let error: f32 = colors
+            .iter()
+            .enumerate()
+            .map(|(i, c)| distance(c, if mask & (1 << i) != 0 { &fg } else { &bg }))
+            .sum();
+        if error < best.3 {
+            best = (mask, fg, bg, error);
+        }
+    }
+
+    let to_u8 = |c: [f32; 3]| c.map(|v| v.round().clamp(0.0, 255.0) as u8);
+    (best.0, to_u8(best.1), to_u8(best.2))
+}
+
+/// Mean color of the sub-pixels selected by `include`.
+fn mean(colors: &[[f32; 3]], include: impl Fn(usize) -> bool) -> [f32; 3] {
+    let mut sum = [0.0; 3];
+    let mut n = 0.0;
+    for (_, c) in colors.iter().enumerate().filter(|(i, _)| include(*i)) {
+        for (total, value) in sum.iter_mut().zip(c) {
+            *total += value;
+        }
+        n += 1.0;
+    }
+    sum.map(|v| v / n)
+}
+
+/// Squared Euclidean RGB distance.
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sextant_code_points() {
+        assert_eq!(sextant_glyph(1), '\u{1FB00}');
+        assert_eq!(sextant_glyph(20), '\u{1FB13}');
+        assert_eq!(sextant_glyph(22), '\u{1FB14}');
+        assert_eq!(sextant_glyph(62), '\u{1FB3B}');
+        assert_eq!(sextant_glyph(21), '▌');
+        assert_eq!(sextant_glyph(63), '█');
+    }
+
+    #[test]
+    fn test_gray_half_block() {
+        // One cell: bright top, dark bottom
+        let grid = render_gray(&[255.0, 0.0], 1, 1, SubCells { columns: 1, rows: 2 }, DitherMode::None);
+        assert_eq!(grid[0][0].ch, '▀');
+    }
+
+    #[test]
+    fn test_color_quadrant_split() {
+        let red = [255, 0, 0];
+        let blue = [0, 0, 255];
+        // Left column red, right column blue
+        let grid = render_color(1, 1, SubCells { columns: 2, rows: 2 }, |x, _| if x == 0 { red } else { blue });
+        let pixel = &grid[0][0];
+        assert_eq!(pixel.ch, '▌');
+        assert_eq!(pixel.rgb, Some(red));
+        assert_eq!(pixel.bg, Some(blue));
+    }
+
+    #[test]
+    fn test_uniform_cell_is_full_block() {
+        let grid = render_color(2, 1, SubCells { columns: 2, rows: 3 }, |_, _| [10, 20, 30]);
+        assert!(grid[0].iter().all(|p| p.ch == '█' && p.rgb == Some([10, 20, 30])));
+    }
+}
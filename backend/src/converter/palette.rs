@@ -0,0 +1,153 @@
+use crate::converter::{color, config::DitherMode, dither};
+
+/// The 16 standard ANSI colors, with xterm's default values.
+pub const ANSI_16: [[u8; 3]; 16] = [
+    [0, 0, 0],       [205, 0, 0],     [0, 205, 0],     [205, 205, 0],
+    [0, 0, 238],     [205, 0, 205],   [0, 205, 205],   [229, 229, 229],
+    [127, 127, 127], [255, 0, 0],     [0, 255, 0],     [255, 255, 0],
+    [92, 92, 255],   [255, 0, 255],   [0, 255, 255],   [255, 255, 255],
+];
+
+/// Channel levels of the xterm 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The xterm 256-color palette: the ANSI 16, the 6x6x6 color cube and a 24-step gray ramp.
+pub fn xterm_256() -> Vec<[u8; 3]> {
+    let mut colors = ANSI_16.to_vec();
+    for r in CUBE_LEVELS {
+        for g in CUBE_LEVELS {
+            for b in CUBE_LEVELS {
+                colors.push([r, g, b]);
+            }
+        }
+    }
+    colors.extend((0..24).map(|i| [8 + i * 10; 3]));
+    colors
+}
+
+/// A fixed set of colors with nearest-color lookup in OKLab.
+pub struct Palette {
+    colors: Vec<[u8; 3]>,
+    lab: Vec<[f32; 3]>,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<[u8; 3]>) -> Self {
+        let lab = colors.iter().map(|&rgb| color::oklab(rgb)).collect();
+        Self { colors, lab }
+    }
+
+    /// Index of the palette entry perceptually closest to an OKLab color.
+    fn nearest(&self, target: [f32; 3]) -> usize {
+        let distance = |lab: &[f32; 3]| (0..3).map(|i| (lab[i] - target[i]).powi(2)).sum::<f32>();
+        self.lab
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Maps a color to its closest palette entry.
+    pub fn map(&self, rgb: [u8; 3]) -> [u8; 3] {
+        self.colors[self.nearest(color::oklab(rgb))]
+    }
+
+    /// Quantizes a row-major grid of optional colors onto the palette.
+    /// Error diffusion runs in OKLab; cells without a color are skipped and neither
+    /// receive nor spread error.
+    pub fn quantize(&self, colors: &[Option<[u8; 3]>], width: usize, height: usize, mode: DitherMode) -> Vec<Option<[u8; 3]>> {
+        debug_assert_eq!(colors.len(), width * height);
+
+        if mode == DitherMode::Bayer {
+            // Offset each cell by its threshold, scaled to the typical spacing of the palette
+            let spread = 255.0 / (self.colors.len() as f32).cbrt();
+            return colors
+                .iter()
+                .enumerate()
+                .map(|(i, rgb)| {
+                    let offset = dither::bayer_threshold(i % width, i / width) * spread;
+                    rgb.map(|rgb| self.map(rgb.map(|v| (v as f32 + offset).round().clamp(0.0, 255.0) as u8)))
+                })
+                .collect();
+        }
+
+        let Some(kernel) = dither::kernel(mode) else {
+            return colors.iter().map(|rgb| rgb.map(|rgb| self.map(rgb))).collect();
+        };
+
+        let mut buffer: Vec<Option<[f32; 3]>> = colors.iter().map(|rgb| rgb.map(color::oklab)).collect();
+        let mut output = Vec::with_capacity(colors.len());
+        for y in 0..height {
+            for x in 0..width {
+                let Some(target) = buffer[y * width + x] else {
+                    output.push(None);
+                    continue;
+                };
+                let index = self.nearest(target);
+                output.push(Some(self.colors[index]));
+
+                let error: [f32; 3] = std::array::from_fn(|c| target[c] - self.lab[index][c]);
+                for &(dx, dy, weight) in kernel {
+                    let nx = x as i32 + dx;
+                    let ny = y as i32 + dy;
+                    if nx < 0 || nx as usize >= width || ny as usize >= height {
+                        continue;
+                    }
+                    if let Some(neighbour) = &mut buffer[ny as usize * width + nx as usize] {
+                        for c in 0..3 {
+                            neighbour[c] += error[c] * weight;
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xterm_256_layout() {
+        let colors = xterm_256();
+        assert_eq!(colors.len(), 256);
+        assert_eq!(colors[16], [0, 0, 0]);
+        assert_eq!(colors[196], [255, 0, 0]);
+        assert_eq!(colors[231], [255, 255, 255]);
+        assert_eq!(colors[232], [8, 8, 8]);
+        assert_eq!(colors[255], [238, 238, 238]);
+    }
+
+    #[test]
+    fn test_nearest_is_perceptual() {
+        let palette = Palette::new(ANSI_16.to_vec());
+        assert_eq!(palette.map([250, 10, 10]), [255, 0, 0]);
+        assert_eq!(palette.map([120, 125, 130]), [127, 127, 127]);
+        // Exact palette colors map to themselves
+        for rgb in ANSI_16 {
+            assert_eq!(palette.map(rgb), rgb);
+        }
+    }
+
+    #[test]
+    fn test_dithering_mixes_colors() {
+        // Mid-grey between black and white should be dithered into a mix of both
+        let palette = Palette::new(vec![[0, 0, 0], [255, 255, 255]]);
+        let colors = vec![Some([188, 188, 188]); 64];
+        for mode in [DitherMode::FloydSteinberg, DitherMode::Atkinson, DitherMode::Bayer] {
+            let output = palette.quantize(&colors, 8, 8, mode);
+            let white = output.iter().filter(|c| **c == Some([255, 255, 255])).count();
+            assert!(white > 0 && white < 64, "{:?} produced {} white cells", mode, white);
+        }
+    }
+
+    #[test]
+    fn test_uncolored_cells_are_kept() {
+        let palette = Palette::new(ANSI_16.to_vec());
+        let output = palette.quantize(&[None, Some([1, 2, 3])], 2, 1, DitherMode::FloydSteinberg);
+        assert_eq!(output, vec![None, Some([0, 0, 0])]);
+    }
+}
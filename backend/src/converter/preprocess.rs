@@ -0,0 +1,189 @@
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use crate::converter::{config::PreprocessConfig, error::ConverterError};
+
+/// Reads the EXIF orientation tag (1-8) from an encoded image, if it has one.
+pub fn exif_orientation(image_bytes: &[u8]) -> Option<u32> {
+    let exif = exif::Reader::new()
+        .read_from_container(&mut std::io::Cursor::new(image_bytes))
+        .ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// Transforms an image so that an EXIF `orientation` (1-8) becomes upright.
+fn orient(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// Checks the parts of the preprocessing config that do not depend on the image.
+pub fn validate(config: &PreprocessConfig) -> Result<(), ConverterError> {
+    if !matches!(config.rotate, 0 | 90 | 180 | 270) {
+        return Err(ConverterError::InvalidParameter("Rotation must be 0, 90, 180 or 270 degrees".into()));
+    }
+    if !config.rotate_degrees.is_finite() {
+        return Err(ConverterError::InvalidParameter("Rotation angle must be a finite number".into()));
+    }
+    if config.crop.is_some_and(|crop| crop.width == 0 || crop.height == 0) {
+        return Err(ConverterError::InvalidParameter("Crop rectangle must not be empty".into()));
+    }
+    Ok(())
+}
+
+/// Applies the EXIF `orientation` (if any), crop, quarter-turn rotation, flips and
+/// free rotation, in that order.
+pub fn apply(img: DynamicImage, orientation: Option<u32>, config: &PreprocessConfig) -> Result<DynamicImage, ConverterError> {
+    let mut img = match orientation.filter(|_| config.auto_orient) {
+        Some(orientation) => orient(img, orientation),
+        None => img,
+    };
+
+    if let Some(crop) = config.crop {
+        let (width, height) = img.dimensions();
+        let fits = crop.x.checked_add(crop.width).is_some_and(|right| right <= width)
+            && crop.y.checked_add(crop.height).is_some_and(|bottom| bottom <= height);
+        if !fits {
+            return Err(ConverterError::InvalidParameter(format!(
+                "Crop rectangle {}x{}+{}+{} is outside the {}x{} image",
+                crop.width, crop.height, crop.x, crop.y, width, height
+            )));
+        }
+        img = img.crop_imm(crop.x, crop.y, crop.width, crop.height);
+    }
+
+    img = match config.rotate {
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        _ => img,
+    };
+    if config.flip_horizontal {
+        img = img.fliph();
+    }
+    if config.flip_vertical {
+        img = img.flipv();
+    }
+
+    let degrees = config.rotate_degrees % 360.0;
+    if degrees != 0.0 {
+        img = DynamicImage::ImageRgba8(rotate_free(&img.to_rgba8(), degrees));
+    }
+    Ok(img)
+}
+
+/// Rotates clockwise by any angle with bilinear sampling. The output canvas is the
+/// bounding box of the rotated image; pixels outside the source are transparent.
+fn rotate_free(img: &RgbaImage, degrees: f32) -> RgbaImage {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (src_width, src_height) = (img.width() as f32, img.height() as f32);
+    let width = (src_width * cos.abs() + src_height * sin.abs()).round().max(1.0) as u32;
+    let height = (src_width * sin.abs() + src_height * cos.abs()).round().max(1.0) as u32;
+
+    let (src_cx, src_cy) = (src_width / 2.0, src_height / 2.0);
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    RgbaImage::from_fn(width, height, |x, y| {
+        // Map the output pixel centre back into the source (inverse rotation)
+        let dx = x as f32 + 0.5 - cx;
+        let dy = y as f32 + 0.5 - cy;
+        let sx = dx * cos + dy * sin + src_cx - 0.5;
+        let sy = -dx * sin + dy * cos + src_cy - 0.5;
+        sample_bilinear(img, sx, sy)
+    })
+}
+
+/// Bilinear sample at a fractional position; positions outside the image read as transparent.
+fn sample_bilinear(img: &RgbaImage, x: f32, y: f32) -> Rgba<u8> {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let fetch = |px: f32, py: f32| {
+        if px < 0.0 || py < 0.0 || px >= img.width() as f32 || py >= img.height() as f32 {
+            [0.0; 4]
+        } else {
+            img.get_pixel(px as u32, py as u32).0.map(|v| v as f32)
+        }
+    };
+
+    let corners = [
+        (fetch(x0, y0), (1.0 - fx) * (1.0 - fy)),
+        (fetch(x0 + 1.0, y0), fx * (1.0 - fy)),
+        (fetch(x0, y0 + 1.0), (1.0 - fx) * fy),
+        (fetch(x0 + 1.0, y0 + 1.0), fx * fy),
+    ];
+    // Weight colors by alpha so transparent neighbours do not darken the edges
+    let alpha: f32 = corners.iter().map(|(p, w)| p[3] * w).sum();
+    if alpha <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let channel = |c: usize| corners.iter().map(|(p, w)| p[c] * p[3] * w).sum::<f32>() / alpha;
+    Rgba([channel(0), channel(1), channel(2), alpha].map(|v| v.round().clamp(0.0, 255.0) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::config::CropRect;
+
+    /// 3x2 image whose pixels encode their own coordinates.
+    fn coordinates() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 2, |x, y| Rgba([x as u8, y as u8, 0, 255])))
+    }
+
+    fn pixel(img: &DynamicImage, x: u32, y: u32) -> [u8; 2] {
+        let p = img.get_pixel(x, y);
+        [p[0], p[1]]
+    }
+
+    #[test]
+    fn test_exif_orientations() {
+        // Orientation 6: stored image must be rotated 90 degrees clockwise to display upright
+        let img = orient(coordinates(), 6);
+        assert_eq!(img.dimensions(), (2, 3));
+        assert_eq!(pixel(&img, 0, 0), [0, 1]);
+        // Orientation 2: mirrored horizontally
+        assert_eq!(pixel(&orient(coordinates(), 2), 0, 0), [2, 0]);
+        // Unknown values leave the image alone
+        assert_eq!(orient(coordinates(), 1).dimensions(), (3, 2));
+    }
+
+    #[test]
+    fn test_crop_rotate_flip_order() {
+        let config = PreprocessConfig {
+            crop: Some(CropRect { x: 1, y: 0, width: 2, height: 2 }),
+            rotate: 90,
+            flip_horizontal: true,
+            ..PreprocessConfig::default()
+        };
+        let img = apply(coordinates(), None, &config).unwrap();
+        // Crop keeps columns 1-2; rotating clockwise puts (1, 1) top-left; the mirror then brings (1, 0) there
+        assert_eq!(img.dimensions(), (2, 2));
+        assert_eq!(pixel(&img, 0, 0), [1, 0]);
+    }
+
+    #[test]
+    fn test_crop_outside_image_is_rejected() {
+        let config = PreprocessConfig {
+            crop: Some(CropRect { x: 2, y: 0, width: 2, height: 1 }),
+            ..PreprocessConfig::default()
+        };
+        assert!(apply(coordinates(), None, &config).is_err());
+    }
+
+    #[test]
+    fn test_free_rotation_expands_canvas() {
+        let img = RgbaImage::from_pixel(10, 10, Rgba([200, 100, 50, 255]));
+        let rotated = rotate_free(&img, 45.0);
+        assert_eq!(rotated.dimensions(), (14, 14));
+        // Corners are outside the source, the centre is not
+        assert_eq!(rotated.get_pixel(0, 0)[3], 0);
+        assert_eq!(*rotated.get_pixel(7, 7), Rgba([200, 100, 50, 255]));
+    }
+}
@@ -0,0 +1,163 @@
+use image::{imageops::FilterType, ImageBuffer, Pixel, Primitive};
+use crate::converter::config::ResizeFilter;
+
+/// Channel types the area-average resizer can accumulate.
+pub trait Sample: Primitive + 'static {
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl Sample for u8 {
+    fn to_f32(self) -> f32 { self as f32 }
+    fn from_f32(value: f32) -> Self { value.round().clamp(0.0, 255.0) as u8 }
+}
+
+impl Sample for f32 {
+    fn to_f32(self) -> f32 { self }
+    fn from_f32(value: f32) -> Self { value }
+}
+
+/// Resizes an 8-bit or floating point image with the configured filter.
+/// `ResizeFilter::Area` integrates every source pixel that covers an output pixel;
+/// the other filters delegate to `image::imageops::resize`.
+pub fn resize<P>(img: &ImageBuffer<P, Vec<P::Subpixel>>, width: u32, height: u32, filter: ResizeFilter) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+    P::Subpixel: Sample,
+{
+    let filter_type = match filter {
+        ResizeFilter::Nearest => FilterType::Nearest,
+        ResizeFilter::Triangle => FilterType::Triangle,
+        ResizeFilter::CatmullRom => FilterType::CatmullRom,
+        ResizeFilter::Gaussian => FilterType::Gaussian,
+        ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+        ResizeFilter::Area => return area_average(img, width, height),
+    };
+    image::imageops::resize(img, width, height, filter_type)
+}
+
+/// Box/area-average resize. Each output pixel is the coverage-weighted mean of all
+/// source pixels under it, which avoids aliasing on large downscales. Done separably:
+/// rows first, then columns.
+fn area_average<P>(img: &ImageBuffer<P, Vec<P::Subpixel>>, width: u32, height: u32) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+    P::Subpixel: Sample,
+{
+    let channels = P::CHANNEL_COUNT as usize;
+    let (src_width, src_height) = (img.width() as usize, img.height() as usize);
+    let (dst_width, dst_height) = (width as usize, height as usize);
+    if src_width == 0 || src_height == 0 || dst_width == 0 || dst_height == 0 {
+        return ImageBuffer::new(width, height);
+    }
+
+    let x_spans = spans(src_width, dst_width);
+    let y_spans = spans(src_height, dst_height);
+    let source = img.as_raw();
+
+    // Horizontal pass: src_height rows of dst_width pixels
+    let mut horizontal = vec![0.0f32; src_height * dst_width * channels];
+    for y in 0..src_height {
+        for (x, span) in x_spans.iter().enumerate() {
+            let out = (y * dst_width + x) * channels;
+            for &(sx, weight) in span {
+                let src = (y * src_width + sx) * channels;
+                for c in 0..channels {
+                    horizontal[out + c] += source[src + c].to_f32() * weight;
+                }
+            }
+        }
+    }
+
+    // Vertical pass into the final buffer
+    let mut output = vec![P::Subpixel::from_f32(0.0); dst_width * dst_height * channels];
+    for (y, span) in y_spans.iter().enumerate() {
+        for x in 0..dst_width {
+            let out = (y * dst_width + x) * channels;
+            for c in 0..channels {
+                let value: f32 = span
+                    .iter()
+                    .map(|&(sy, weight)| horizontal[(sy * dst_width + x) * channels + c] * weight)
+                    .sum();
+                output[out + c] = P::Subpixel::from_f32(value);
+            }
+        }
+    }
+
+    ImageBuffer::from_raw(width, height, output).expect("buffer size matches dimensions")
+}
+
+/// For each destination index, the source indices it covers and their normalized weights.
+fn spans(src_len: usize, dst_len: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src_len as f32 / dst_len as f32;
+    (0..dst_len)
+        .map(|i| {
+            let start = i as f32 * scale;
+            let end = (start + scale).min(src_len as f32);
+            let first = start.floor() as usize;
+            let last = (end.ceil() as usize).clamp(first + 1, src_len);
+
+            let mut span: Vec<(usize, f32)> = (first..last)
+                .map(|s| {
+                    let overlap = end.min(s as f32 + 1.0) - start.max(s as f32);
+                    (s, overlap.max(0.0))
+                })
+                .filter(|&(_, w)| w > 0.0)
+                .collect();
+            if span.is_empty() {
+                span.push((first.min(src_len - 1), 1.0));
+            }
+            let total: f32 = span.iter().map(|&(_, w)| w).sum();
+            for entry in &mut span {
+                entry.1 /= total;
+            }
+            span
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    #[test]
+    fn test_area_average_of_checkerboard() {
+        // A 1-pixel checkerboard averages to mid-grey, where nearest-neighbour would alias
+        let img = GrayImage::from_fn(8, 8, |x, y| Luma([if (x + y) % 2 == 0 { 0 } else { 255 }]));
+        let small = resize(&img, 2, 2, ResizeFilter::Area);
+        assert!(small.pixels().all(|p| (127..=128).contains(&p[0])));
+    }
+
+    #[test]
+    fn test_area_average_fractional_coverage() {
+        // 3 -> 2: each output covers 1.5 source pixels
+        let img = RgbImage::from_fn(3, 1, |x, _| Rgb([[0, 90, 180][x as usize]; 3]));
+        let small = resize(&img, 2, 1, ResizeFilter::Area);
+        assert_eq!(small.get_pixel(0, 0)[0], 30);
+        assert_eq!(small.get_pixel(1, 0)[0], 150);
+    }
+
+    #[test]
+    fn test_area_upscale_keeps_values() {
+        let img = GrayImage::from_fn(2, 1, |x, _| Luma([if x == 0 { 10 } else { 200 }]));
+        let large = resize(&img, 4, 2, ResizeFilter::Area);
+        assert_eq!(large.get_pixel(0, 1)[0], 10);
+        assert_eq!(large.get_pixel(3, 0)[0], 200);
+    }
+
+    #[test]
+    fn test_area_average_float() {
+        let img = image::Rgb32FImage::from_fn(4, 1, |x, _| Rgb([x as f32 / 3.0; 3]));
+        let small = resize(&img, 1, 1, ResizeFilter::Area);
+        assert!((small.get_pixel(0, 0)[0] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_named_filters_resize() {
+        let img = RgbImage::new(10, 10);
+        for filter in [ResizeFilter::Nearest, ResizeFilter::Triangle, ResizeFilter::CatmullRom, ResizeFilter::Gaussian, ResizeFilter::Lanczos3] {
+            assert_eq!(resize(&img, 4, 3, filter).dimensions(), (4, 3));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::converter::config::ThresholdMethod;
+
+/// Parameters of the binary threshold methods.
+#[derive(Debug, Clone, Copy)]
+pub struct ThresholdSettings {
+    /// Cut-off of `ThresholdMethod::Fixed`.
+    pub threshold: u8,
+    /// Side length, in cells, of the neighbourhood averaged by `ThresholdMethod::Adaptive`.
+    pub window: u32,
+    /// Amount subtracted from the local mean by `ThresholdMethod::Adaptive`.
+    pub offset: f32,
+}
+
+/// Splits a row-major intensity grid into bright (`true`) and dark cells.
+pub fn binarize(intensities: &[u8], width: usize, height: usize, method: ThresholdMethod, settings: ThresholdSettings) -> Vec<bool> {
+    match method {
+        ThresholdMethod::Fixed => intensities.iter().map(|&v| v >= settings.threshold).collect(),
+        ThresholdMethod::Otsu => {
+            let threshold = otsu(intensities);
+            intensities.iter().map(|&v| v >= threshold).collect()
+        }
+        ThresholdMethod::Adaptive => adaptive(intensities, width, height, settings.window, settings.offset),
+    }
+}
+
+/// Otsu's threshold: the cut-off that maximizes the variance between the dark and bright
+/// classes of the histogram. Intensities at or above it are bright. A grid with a single
+/// intensity has no such split and falls back to 128.
+pub fn otsu(intensities: &[u8]) -> u8 {
+    let mut histogram = [0u64; 256];
+    for &v in intensities {
+        histogram[v as usize] += 1;
+    }
+    let total = intensities.len() as f64;
+    let sum: f64 = histogram.iter().enumerate().map(|(v, &count)| v as f64 * count as f64).sum();
+
+    let (mut dark_count, mut dark_sum) = (0.0, 0.0);
+    let (mut best, mut best_variance) = (128, 0.0);
+    // Splitting after the last level leaves the bright class empty, so it is never a candidate
+    for (level, &count) in histogram.iter().enumerate().take(255) {
+        dark_count += count as f64;
+        dark_sum += level as f64 * count as f64;
+        let bright_count = total - dark_count;
+        if dark_count == 0.0 || bright_count == 0.0 {
+            continue;
+        }
+        let mean_difference = dark_sum / dark_count - (sum - dark_sum) / bright_count;
+        let variance = dark_count * bright_count * mean_difference * mean_difference;
+        if variance > best_variance {
+            best_variance = variance;
+            best = level + 1;
+        }
+    }
+    best as u8
+}
+
+/// Adaptive thresholding: a cell is bright if it is above the mean of the `window` x `window`
+/// cells around it minus `offset`. Handles uneven lighting that defeats a global threshold.
+/// Uses a summed-area table, so the cost does not depend on the window size.
+fn adaptive(intensities: &[u8], width: usize, height: usize, window: u32, offset: f32) -> Vec<bool> {
+    let stride = width + 1;
+    let mut integral = vec![0u64; stride * (height + 1)];
+    for y in 0..height {
+        let mut row_sum = 0;
+        for x in 0..width {
+            row_sum += intensities[y * width + x] as u64;
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    let radius = window as usize / 2;
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (x0, x1) = (x.saturating_sub(radius), (x + radius + 1).min(width));
+            let (y0, y1) = (y.saturating_sub(radius), (y + radius + 1).min(height));
+            let sum = integral[y1 * stride + x1] + integral[y0 * stride + x0]
+                - integral[y0 * stride + x1]
+                - integral[y1 * stride + x0];
+            let mean = sum as f32 / ((x1 - x0) * (y1 - y0)) as f32;
+            intensities[y * width + x] as f32 > mean - offset
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: ThresholdSettings = ThresholdSettings { threshold: 100, window: 3, offset: 10.0 };
+
+    #[test]
+    fn test_fixed_threshold() {
+        let bright = binarize(&[0, 99, 100, 255], 4, 1, ThresholdMethod::Fixed, SETTINGS);
+        assert_eq!(bright, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn test_otsu_splits_bimodal_histogram() {
+        let intensities: Vec<u8> = [20, 30, 40].repeat(10).into_iter().chain([200, 210, 220].repeat(5)).collect();
+        let threshold = otsu(&intensities);
+        assert!(threshold > 40 && threshold <= 200, "{}", threshold);
+        assert_eq!(otsu(&[77; 16]), 128);
+        assert_eq!(otsu(&[0, 255]), 1);
+    }
+
+    #[test]
+    fn test_adaptive_follows_uneven_lighting() {
+        // A dark stroke (one column) on a background that brightens from left to right.
+        // The stroke is brighter than the left background, so no global threshold works
+        let (width, height) = (9, 3);
+        let intensities: Vec<u8> = (0..height)
+            .flat_map(|_| (0..width).map(|x| if x == 6 { 150 } else { 100 + x as u8 * 15 }))
+            .collect();
+        let bright = binarize(&intensities, width, height, ThresholdMethod::Adaptive, SETTINGS);
+        for y in 0..height {
+            let row = &bright[y * width..(y + 1) * width];
+            assert!(!row[6], "stroke should be dark in row {}", y);
+            assert!(row.iter().enumerate().all(|(x, &b)| x == 6 || b), "row {}: {:?}", y, row);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::converter::config::ToneCurve;
+
+/// Parameters of the tone curves.
+#[derive(Debug, Clone, Copy)]
+pub struct ToneSettings<'a> {
+    /// Gamma of `ToneCurve::Gamma`; values above 1 brighten the midtones.
+    pub gamma: f32,
+    /// Strength of `ToneCurve::SCurve` in -1..=1; negative values flatten contrast instead.
+    pub s_curve_strength: f32,
+    /// `[input, output]` control points of `ToneCurve::Points`, with strictly increasing inputs.
+    pub points: &'a [[u8; 2]],
+}
+
+/// Builds the 256-entry lookup table for a tone curve.
+pub fn lut(curve: ToneCurve, settings: ToneSettings) -> [u8; 256] {
+    let map = |v: u8| -> f32 {
+        let x = v as f32 / 255.0;
+        match curve {
+            ToneCurve::None => x,
+            ToneCurve::Gamma => x.powf(1.0 / settings.gamma),
+            ToneCurve::SCurve => {
+                let smooth = x * x * (3.0 - 2.0 * x);
+                x + settings.s_curve_strength * (smooth - x)
+            }
+            ToneCurve::Points => interpolate(settings.points, v as f32) / 255.0,
+        }
+    };
+    std::array::from_fn(|v| (map(v as u8) * 255.0).round().clamp(0.0, 255.0) as u8)
+}
+
+/// Monotone cubic (Fritsch–Carlson) interpolation through the control points, so the
+/// curve is smooth but never overshoots between them. Inputs outside the first and last
+/// points hold the end values.
+fn interpolate(points: &[[u8; 2]], x: f32) -> f32 {
+    let xs: Vec<f32> = points.iter().map(|p| p[0] as f32).collect();
+    let ys: Vec<f32> = points.iter().map(|p| p[1] as f32).collect();
+    let n = points.len();
+    if x <= xs[0] {
+        return ys[0];
+    }
+    if x >= xs[n - 1] {
+        return ys[n - 1];
+    }
+
+    let slopes: Vec<f32> = (0..n - 1).map(|i| (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i])).collect();
+    let mut tangents = vec![0.0; n];
+    tangents[0] = slopes[0];
+    tangents[n - 1] = slopes[n - 2];
+    for i in 1..n - 1 {
+        // Flat at local extrema, harmonic mean of the neighbouring slopes elsewhere
+        if slopes[i - 1] * slopes[i] > 0.0 {
+            tangents[i] = 2.0 / (1.0 / slopes[i - 1] + 1.0 / slopes[i]);
+        }
+    }
+
+    let i = xs.windows(2).position(|pair| x < pair[1]).unwrap_or(n - 2);
+    let h = xs[i + 1] - xs[i];
+    let t = (x - xs[i]) / h;
+    let (t2, t3) = (t * t, t * t * t);
+    (2.0 * t3 - 3.0 * t2 + 1.0) * ys[i]
+        + (t3 - 2.0 * t2 + t) * h * tangents[i]
+        + (-2.0 * t3 + 3.0 * t2) * ys[i + 1]
+        + (t3 - t2) * h * tangents[i + 1]
+}
+
+/// Index of the character whose threshold range contains `intensity`: the last
+/// threshold that is not above it. Intensities below the first threshold use the first character.
+pub fn threshold_index(intensity: u8, thresholds: &[u8]) -> usize {
+    thresholds.partition_point(|&start| start <= intensity).saturating_sub(1)
+}
+
+/// Representative intensity of each threshold range (its midpoint), used as the target
+/// levels when dithering onto explicit thresholds.
+pub fn threshold_levels(thresholds: &[u8]) -> Vec<f32> {
+    thresholds
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = thresholds.get(i + 1).map_or(256.0, |&next| next as f32);
+            (start as f32 + end) / 2.0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(points: &[[u8; 2]]) -> ToneSettings<'_> {
+        ToneSettings { gamma: 2.0, s_curve_strength: 1.0, points }
+    }
+
+    #[test]
+    fn test_curves_keep_endpoints() {
+        for curve in [ToneCurve::None, ToneCurve::Gamma, ToneCurve::SCurve, ToneCurve::Points] {
+            let table = lut(curve, settings(&[[0, 0], [128, 200], [255, 255]]));
+            assert_eq!((table[0], table[255]), (0, 255), "{:?}", curve);
+            assert!(table.windows(2).all(|pair| pair[0] <= pair[1]), "{:?} is not monotone", curve);
+        }
+        assert_eq!(lut(ToneCurve::None, settings(&[])), std::array::from_fn(|v| v as u8));
+    }
+
+    #[test]
+    fn test_curve_shapes() {
+        // Gamma 2 brightens the midtones; the S-curve darkens shadows and lifts highlights
+        assert_eq!(lut(ToneCurve::Gamma, settings(&[]))[64], 128);
+        let s_curve = lut(ToneCurve::SCurve, settings(&[]));
+        assert!(s_curve[64] < 64 && s_curve[192] > 192);
+        assert_eq!(s_curve[128], 128);
+    }
+
+    #[test]
+    fn test_control_points_are_interpolated() {
+        let table = lut(ToneCurve::Points, settings(&[[32, 0], [128, 200], [224, 255]]));
+        assert_eq!(table[10], 0);
+        assert_eq!(table[128], 200);
+        assert_eq!(table[240], 255);
+        assert!(table[80] > 0 && table[80] < 200);
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let thresholds = [0, 10, 200];
+        assert_eq!(threshold_index(0, &thresholds), 0);
+        assert_eq!(threshold_index(9, &thresholds), 0);
+        assert_eq!(threshold_index(10, &thresholds), 1);
+        assert_eq!(threshold_index(255, &thresholds), 2);
+        assert_eq!(threshold_index(5, &[20, 40]), 0);
+        assert_eq!(threshold_levels(&thresholds), vec![5.0, 105.0, 228.0]);
+    }
+}
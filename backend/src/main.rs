@@ -8,13 +8,14 @@ use futures_util::StreamExt as _;
 use bytes::BytesMut;
 
 // Import the types from the converter module
-use converter::{Converter, ConverterConfig};
+use converter::{charsets, Converter, ConverterConfig};
 use request_logger::RequestLogger;
 
-/// Parses the multipart payload, extracting the image and config JSON (if present).
-async fn parse_multipart(mut payload: Multipart) -> Result<(BytesMut, Option<BytesMut>), rusty_api::HttpResponse> {
+/// Parses the multipart payload, extracting the image, config JSON and font (if present).
+async fn parse_multipart(mut payload: Multipart) -> Result<(BytesMut, Option<BytesMut>, Option<BytesMut>), rusty_api::HttpResponse> {
     let mut image_bytes = BytesMut::new();
     let mut config_json = None;
+    let mut font_bytes = None;
 
     while let Some(item) = payload.next().await {
         let mut field = match item {
@@ -43,6 +44,17 @@ async fn parse_multipart(mut payload: Multipart) -> Result<(BytesMut, Option<Byt
                 }
                 config_json = Some(config_bytes);
             }
+            "font" => {
+                let mut font_data = BytesMut::new();
+                while let Some(chunk) = field.next().await {
+                    let data = match chunk {
+                        Ok(d) => d,
+                        Err(e) => return Err(rusty_api::HttpResponse::InternalServerError().body(format!("Font read error: {e}"))),
+                    };
+                    font_data.extend_from_slice(&data);
+                }
+                font_bytes = Some(font_data);
+            }
             _ => {
                 return Err(rusty_api::HttpResponse::BadRequest()
                     .body(format!("Unexpected field: {}", field.name())));
@@ -50,7 +62,57 @@ async fn parse_multipart(mut payload: Multipart) -> Result<(BytesMut, Option<Byt
         }
     }
 
-    Ok((image_bytes, config_json))
+    Ok((image_bytes, config_json, font_bytes))
+}
+
+/// Parses the config JSON if provided, otherwise uses the default config.
+/// An uploaded font is attached for density calibration.
+fn parse_config(config_json: Option<BytesMut>, font_bytes: Option<BytesMut>, logger: &RequestLogger) -> Result<ConverterConfig, rusty_api::HttpResponse> {
+    let mut config = match config_json {
+        Some(config_bytes) => serde_json::from_slice::<ConverterConfig>(&config_bytes).map_err(|e| {
+            logger.error(format!("Invalid config JSON: {}", e));
+            rusty_api::HttpResponse::BadRequest().body(format!("Invalid config JSON: {}", e))
+        })?,
+        None => ConverterConfig::default(),
+    };
+    config.calibration_font = font_bytes.map(|bytes| bytes.to_vec());
+    Ok(config)
+}
+
+/// Sends data as plain JSON (used when compression is disabled or fails).
+fn json_response<T: serde::Serialize>(data: &T) -> rusty_api::HttpResponse {
+    match serde_json::to_string(data) {
+        Ok(json) => rusty_api::HttpResponse::Ok()
+            .content_type("application/json")
+            .body(json),
+        Err(e) => rusty_api::HttpResponse::InternalServerError()
+            .body(format!("Serialization failed: {}", e)),
+    }
+}
+
+/// Sends RLE + gzip compressed data with the compression headers, logging the savings.
+/// `original_size` is the size of the uncompressed JSON.
+fn compressed_response(compressed: Vec<u8>, original_size: usize, logger: &RequestLogger) -> rusty_api::HttpResponse {
+    let compressed_size = compressed.len();
+    let compression_percentage = ((original_size as f64 - compressed_size as f64) / original_size as f64) * 100.0;
+
+    // Convert bytes to megabytes for logging
+    let original_mb = original_size as f64 / (1024.0 * 1024.0);
+    let compressed_mb = compressed_size as f64 / (1024.0 * 1024.0);
+
+    logger.info(format!("Compressed by {:.1}% ({:.2} MB -> {:.2} MB, {} -> {} bytes)", 
+        compression_percentage, original_mb, compressed_mb, original_size, compressed_size));
+
+    // Create a custom header string to include compression info
+    let compression_header = format!("rle-gzip;original={};compressed={};percentage={:.1}", 
+        original_size, compressed_size, compression_percentage);
+
+    rusty_api::HttpResponse::Ok()
+        .content_type("application/octet-stream")
+        .insert_header(("X-Compression", compression_header.as_str()))
+        .insert_header(("X-Original-Size", original_size.to_string().as_str()))
+        .insert_header(("X-Compressed-Size", compressed_size.to_string().as_str()))
+        .body(compressed)
 }
 
 /// Main route handler for image-to-ASCII conversion.
@@ -66,8 +128,8 @@ async fn convert_image_route(payload: Multipart) -> rusty_api::HttpResponse {
     logger.info("Processing image conversion request");
 
     // Parse multipart payload
-    let (image_bytes, config_json) = match parse_multipart(payload).await {
-        Ok((img, cfg)) => (img, cfg),
+    let (image_bytes, config_json, font_bytes) = match parse_multipart(payload).await {
+        Ok((img, cfg, font)) => (img, cfg, font),
         Err(response) => return response,
     };
 
@@ -76,17 +138,9 @@ async fn convert_image_route(payload: Multipart) -> rusty_api::HttpResponse {
         return rusty_api::HttpResponse::BadRequest().body("No image data provided");
     }
 
-    // Parse config if provided, otherwise use default
-    let config = if let Some(config_bytes) = config_json {
-        match serde_json::from_slice::<ConverterConfig>(&config_bytes) {
-            Ok(cfg) => cfg,
-            Err(e) => {
-                logger.error(format!("Invalid config JSON: {}", e));
-                return rusty_api::HttpResponse::BadRequest().body(format!("Invalid config JSON: {}", e));
-            }
-        }
-    } else {
-        ConverterConfig::default()
+    let config = match parse_config(config_json, font_bytes, &logger) {
+        Ok(cfg) => cfg,
+        Err(response) => return response,
     };
 
     // Convert image and optionally compress
@@ -102,48 +156,17 @@ async fn convert_image_route(payload: Multipart) -> rusty_api::HttpResponse {
                 match compressor::compress_ascii_grid(&ascii_grid) {
                     Ok(compressed) => {
                         let original_size = serde_json::to_string(&ascii_grid).unwrap_or_default().len();
-                        let compressed_size = compressed.len();
-                        let compression_percentage = ((original_size - compressed_size) as f64 / original_size as f64) * 100.0;
-                        
-                        // Convert bytes to megabytes for logging
-                        let original_mb = original_size as f64 / (1024.0 * 1024.0);
-                        let compressed_mb = compressed_size as f64 / (1024.0 * 1024.0);
-                        
-                        logger.info(format!("Compressed by {:.1}% ({:.2} MB -> {:.2} MB, {} -> {} bytes)", 
-                            compression_percentage, original_mb, compressed_mb, original_size, compressed_size));
-                        
-                        // Create a custom header string to include compression info
-                        let compression_header = format!("rle-gzip;original={};compressed={};percentage={:.1}", 
-                            original_size, compressed_size, compression_percentage);
-                        
-                        rusty_api::HttpResponse::Ok()
-                            .content_type("application/octet-stream")
-                            .insert_header(("X-Compression", compression_header.as_str()))
-                            .insert_header(("X-Original-Size", original_size.to_string().as_str()))
-                            .insert_header(("X-Compressed-Size", compressed_size.to_string().as_str()))
-                            .body(compressed)
+                        compressed_response(compressed, original_size, &logger)
                     },
                     Err(e) => {
                         logger.error(format!("Compression failed: {}", e));
                         // Fall back to uncompressed
-                        match serde_json::to_string(&ascii_grid) {
-                            Ok(json) => rusty_api::HttpResponse::Ok()
-                                .content_type("application/json")
-                                .body(json),
-                            Err(e) => rusty_api::HttpResponse::InternalServerError()
-                                .body(format!("Serialization failed: {}", e)),
-                        }
+                        json_response(&ascii_grid)
                     }
                 }
             } else {
                 // Send uncompressed JSON
-                match serde_json::to_string(&ascii_grid) {
-                    Ok(json) => rusty_api::HttpResponse::Ok()
-                        .content_type("application/json")
-                        .body(json),
-                    Err(e) => rusty_api::HttpResponse::InternalServerError()
-                        .body(format!("Serialization failed: {}", e)),
-                }
+                json_response(&ascii_grid)
             }
         },
         Err(e) => {
@@ -154,10 +177,106 @@ async fn convert_image_route(payload: Multipart) -> rusty_api::HttpResponse {
     }
 }
 
+/// Route handler for animated images (GIF, APNG, animated WebP).
+/// Accepts the same fields as `/convert-image` and returns every frame with its delay.
+async fn convert_animation_route(payload: Multipart) -> rusty_api::HttpResponse {
+    // Generate a request ID for logging
+    let request_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    let logger = RequestLogger::new(request_id);
+    logger.info("Processing animation conversion request");
+
+    // Parse multipart payload
+    let (image_bytes, config_json, font_bytes) = match parse_multipart(payload).await {
+        Ok((img, cfg, font)) => (img, cfg, font),
+        Err(response) => return response,
+    };
+
+    if image_bytes.is_empty() {
+        logger.error("No image data provided");
+        return rusty_api::HttpResponse::BadRequest().body("No image data provided");
+    }
+
+    let config = match parse_config(config_json, font_bytes, &logger) {
+        Ok(cfg) => cfg,
+        Err(response) => return response,
+    };
+
+    match Converter::convert_animation_from_bytes(&image_bytes, config) {
+        Ok(animation) => {
+            logger.info(format!("Animation converted successfully ({} frames)", animation.frames.len()));
+
+            match compressor::compress_ascii_animation(&animation) {
+                Ok(compressed) => {
+                    let original_size = serde_json::to_string(&animation).unwrap_or_default().len();
+                    compressed_response(compressed, original_size, &logger)
+                },
+                Err(e) => {
+                    logger.error(format!("Compression failed: {}", e));
+                    // Fall back to uncompressed
+                    json_response(&animation)
+                }
+            }
+        },
+        Err(e) => {
+            logger.error(format!("Animation conversion failed: {}", e));
+            rusty_api::HttpResponse::InternalServerError()
+                .body(format!("Animation conversion failed: {}", e))
+        },
+    }
+}
+
+/// Route handler for density calibration of a character set.
+/// Accepts multipart form-data with optional "config" and "font" fields (no image) and
+/// returns the calibrated character set with the measured per-glyph weights.
+async fn calibrate_charset_route(payload: Multipart) -> rusty_api::HttpResponse {
+    // Generate a request ID for logging
+    let request_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    let logger = RequestLogger::new(request_id);
+    logger.info("Processing character set calibration request");
+
+    let (_, config_json, font_bytes) = match parse_multipart(payload).await {
+        Ok((img, cfg, font)) => (img, cfg, font),
+        Err(response) => return response,
+    };
+
+    let config = match parse_config(config_json, font_bytes, &logger) {
+        Ok(cfg) => cfg,
+        Err(response) => return response,
+    };
+
+    match Converter::calibrate(&config) {
+        Ok(calibration) => {
+            logger.info(format!("Calibrated {} glyphs", calibration.glyphs.len()));
+            json_response(&calibration)
+        },
+        Err(e) => {
+            logger.error(format!("Calibration failed: {}", e));
+            rusty_api::HttpResponse::BadRequest()
+                .body(format!("Calibration failed: {}", e))
+        },
+    }
+}
+
+/// Lists the built-in character set presets that `character_set` accepts by name.
+async fn list_charsets_route() -> rusty_api::HttpResponse {
+    json_response(&charsets::PRESETS)
+}
+
 /// Entrypoint: sets up API routes, TLS, CORS, and starts the server.
 fn main() {
     let routes = rusty_api::Routes::new()
-        .add_route(rusty_api::Method::POST, "/convert-image", convert_image_route);
+        .add_route(rusty_api::Method::POST, "/convert-image", convert_image_route)
+        .add_route(rusty_api::Method::POST, "/convert-animation", convert_animation_route)
+        .add_route(rusty_api::Method::GET, "/charsets", list_charsets_route)
+        .add_route(rusty_api::Method::POST, "/calibrate-charset", calibrate_charset_route);
 
     rusty_api::Api::new()
         .certs("certs/cert.pem", "certs/key.pem")